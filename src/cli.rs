@@ -0,0 +1,195 @@
+use std::{io::{self, BufRead, Write}, path::{Path, PathBuf}, process::ExitCode};
+
+use anyhow::{Error, Result};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use tokio::runtime::Runtime;
+
+use crate::{core::{async_encrypt_file_to, async_get_key, async_shred_verified, load_settings, AlreadyExists, DetectedKey, Overwrite, Problem, Settings}, gui::{tr, tr_args, tr_error, Locale}};
+
+#[derive(Debug, Default)]
+struct EncryptArgs {
+    public_key: PathBuf,
+    private_key: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    shred: bool,
+    paths: Vec<PathBuf>,
+}
+
+fn parse_encrypt_args(args: &[String]) -> Result<EncryptArgs> {
+    let mut parsed = EncryptArgs::default();
+    let mut public_key = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--public-key" | "--private-key" | "--output-dir" => {
+                let value = args.next().map(PathBuf::from).ok_or(Problem::new("cli-missing-value").arg("option", arg))?;
+                match arg.as_str() {
+                    "--public-key" => public_key = Some(value),
+                    "--private-key" => parsed.private_key = Some(value),
+                    _ => parsed.output_dir = Some(value),
+                }
+            }
+            "--shred" => parsed.shred = true,
+            "--" => parsed.paths.extend(args.by_ref().map(PathBuf::from)),
+            option if option.starts_with("--") => return Err(Error::new(Problem::new("cli-unknown-option").arg("option", option))),
+            path => parsed.paths.push(PathBuf::from(path)),
+        }
+    }
+    parsed.public_key = public_key.ok_or(Problem::new("cli-missing-public-key"))?;
+    if parsed.paths.is_empty() {
+        return Err(Error::new(Problem::new("cli-missing-paths")));
+    }
+    if parsed.shred && parsed.private_key.is_none() {
+        return Err(Error::new(Problem::new("cli-shred-needs-private-key")));
+    }
+    Ok(parsed)
+}
+
+// Shredding can't be undone, so it only happens once "yes" was typed with
+// the warning on screen. Scripts can pipe the answer in.
+fn confirm_shred(locale: Locale) -> Result<bool> {
+    eprintln!("{}\n{}", tr(locale, "shred-explanation"), tr(locale, "shred-warning"));
+    eprint!("{} ", tr(locale, "cli-shred-confirm"));
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(answer.trim() == "yes")
+}
+
+// The private key is only needed to verify the output before shredding, so
+// it has to belong to the public key the files are encrypted to.
+async fn async_load_keys(public_key: PathBuf, private_key: Option<PathBuf>) -> Result<(RsaPublicKey, Option<RsaPrivateKey>)> {
+    let public_key = match async_get_key(public_key, None).await? {
+        (DetectedKey::Public(public_key), _) => public_key,
+        (DetectedKey::Private(private_key), _) => private_key.to_public_key(),
+    };
+    let private_key = match private_key {
+        Some(path) => match async_get_key(path.clone(), None).await? {
+            (DetectedKey::Private(private_key), _) if private_key.to_public_key() == public_key => Some(*private_key),
+            (DetectedKey::Private(_), _) => return Err(Error::new(Problem::new("cli-key-mismatch"))),
+            (DetectedKey::Public(_), _) => return Err(Error::new(Problem::new("cli-not-private-key").arg("path", path.display()))),
+        },
+        None => None,
+    };
+    Ok((public_key, private_key))
+}
+
+async fn async_encrypt(locale: Locale, settings: Settings, public_key: RsaPublicKey, private_key: Option<RsaPrivateKey>, args: EncryptArgs) -> ExitCode {
+    let options = settings.seal_options();
+    let mut failed = false;
+    for path in args.paths {
+        // Without a directory on the command line or in the preferences, the
+        // output goes next to the original.
+        let output_dir = args
+            .output_dir
+            .clone()
+            .or(settings.output_dir.clone())
+            .unwrap_or(path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new(".")).to_path_buf());
+        let input = path.display().to_string();
+        let result = async_encrypt_file_to(public_key.clone(), options, path.clone(), output_dir, settings.output_encoding, Overwrite::Ask).await;
+        let line = match (result, &private_key) {
+            (Ok(output_path), Some(private_key)) => {
+                let output = output_path.display().to_string();
+                match async_shred_verified(private_key.clone(), path, output_path).await {
+                    Ok(true) => Ok(tr_args(locale, "cli-shredded", &[("input", input.as_str().into()), ("output", output.into())])),
+                    Ok(false) => {
+                        let args = [("input", input.as_str().into()), ("output", output.into()), ("warning", tr(locale, "shred-original-kept").into())];
+                        Ok(tr_args(locale, "cli-encrypted-warning", &args))
+                    }
+                    Err(err) => Err(tr_args(locale, "shred-failed", &[("path", output.into()), ("error", tr_error(locale, &err).into())])),
+                }
+            }
+            (Ok(output_path), None) => Ok(tr_args(locale, "cli-encrypted", &[("input", input.as_str().into()), ("output", output_path.display().to_string().into())])),
+            (Err(err), _) if err.is::<AlreadyExists>() => {
+                let path = err.downcast::<AlreadyExists>().map(|exists| exists.0).unwrap_or_default();
+                Err(tr_args(locale, "job-skipped", &[("path", path.display().to_string().into())]))
+            }
+            (Err(err), _) => Err(tr_error(locale, &err)),
+        };
+        match line {
+            Ok(line) => println!("{}", line),
+            Err(err) => {
+                eprintln!("{}", tr_args(locale, "cli-failed", &[("input", input.into()), ("error", err.into())]));
+                failed = true;
+            }
+        }
+    }
+    match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    }
+}
+
+// `rsa-tool encrypt` runs with the saved preferences, the same ones the GUI
+// would use. Existing outputs are never replaced.
+pub fn run_cli(args: Vec<String>) -> Result<ExitCode> {
+    let (settings, load_error) = match load_settings() {
+        Ok(settings) => (settings, None),
+        Err(err) => (Settings::default(), Some(err)),
+    };
+    let locale = Locale::resolve(settings.language);
+    if let Some(err) = load_error {
+        let args = [("operation", tr(locale, "op-load-settings").into()), ("error", tr_error(locale, &err).into())];
+        eprintln!("{}", tr_args(locale, "toast-failed", &args));
+    }
+    match args.first().map(String::as_str) {
+        Some("encrypt") => {}
+        Some("help" | "--help" | "-h") => {
+            println!("{}", tr(locale, "cli-usage"));
+            return Ok(ExitCode::SUCCESS);
+        }
+        _ => {
+            eprintln!("{}", tr(locale, "cli-usage"));
+            return Ok(ExitCode::from(2));
+        }
+    }
+    let args = match parse_encrypt_args(&args[1..]) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}\n\n{}", tr_error(locale, &err), tr(locale, "cli-usage"));
+            return Ok(ExitCode::from(2));
+        }
+    };
+    // The keys are checked first, so the confirmation is only asked for
+    // when the files can actually be verified.
+    let runtime = Runtime::new()?;
+    let (public_key, private_key) = match runtime.block_on(async_load_keys(args.public_key.clone(), args.private_key.clone())) {
+        Ok(keys) => keys,
+        Err(err) => {
+            eprintln!("{}", tr_error(locale, &err));
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    if args.shred && !confirm_shred(locale)? {
+        eprintln!("{}", tr(locale, "cli-shred-declined"));
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(runtime.block_on(async_encrypt(locale, settings, public_key, private_key, args)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::parse_encrypt_args;
+    use crate::core::Problem;
+
+    fn parse(args: &[&str]) -> Result<super::EncryptArgs, &'static str> {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        parse_encrypt_args(&args).map_err(|err| err.downcast_ref::<Problem>().unwrap().id)
+    }
+
+    #[test]
+    fn shredding_needs_the_private_key_to_verify() {
+        assert_eq!(parse(&["--shred", "a.txt"]).unwrap_err(), "cli-missing-public-key");
+        assert_eq!(parse(&["--public-key", "pub.pem", "--shred", "a.txt"]).unwrap_err(), "cli-shred-needs-private-key");
+        assert_eq!(parse(&["--public-key", "pub.pem", "--shred"]).unwrap_err(), "cli-missing-paths");
+        assert_eq!(parse(&["--public-key"]).unwrap_err(), "cli-missing-value");
+        assert_eq!(parse(&["--public-key", "pub.pem", "--force", "a.txt"]).unwrap_err(), "cli-unknown-option");
+        let args = parse(&["--public-key", "pub.pem", "--private-key", "key.pem", "--shred", "a.txt", "--", "--b.txt"]).unwrap();
+        assert!(args.shred);
+        assert_eq!(args.private_key, Some(PathBuf::from("key.pem")));
+        assert_eq!(args.paths, [PathBuf::from("a.txt"), PathBuf::from("--b.txt")]);
+        assert!(!parse(&["--public-key", "pub.pem", "a.txt"]).unwrap().shred);
+    }
+}
@@ -0,0 +1,243 @@
+use std::{collections::HashSet, ffi::OsStr, fs, path::{Component, Path, PathBuf}};
+
+use anyhow::{Error, Result};
+use tar::{Archive, Builder, HeaderMode};
+
+pub fn pack_dir(path: &Path) -> Result<Vec<u8>> {
+    let name = path.file_name().ok_or(Error::msg("Invalid directory name!"))?;
+    let mut links = Vec::new();
+    collect_dir_links(path, Path::new(name), &mut links)?;
+    check_links(&links)?;
+    let mut builder = Builder::new(Vec::new());
+    builder.mode(HeaderMode::Complete);
+    builder.follow_symlinks(false);
+    builder.append_dir_all(name, path)?;
+    builder.into_inner().map_err(Error::new)
+}
+
+// Refuses symlinks that `unpack` would reject, so a directory that
+// encrypts fine can always be decrypted again.
+fn collect_dir_links(dir: &Path, archive_dir: &Path, links: &mut Vec<(PathBuf, PathBuf)>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let archive_path = archive_dir.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_symlink() {
+            links.push((archive_path, fs::read_link(entry.path())?));
+        } else if file_type.is_dir() {
+            collect_dir_links(&entry.path(), &archive_path, links)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_path(path: &Path) -> Result<()> {
+    let is_safe = path.components().next().is_some()
+        && path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    match is_safe {
+        true => Ok(()),
+        false => Err(Error::msg(format!("Refusing unsafe path in archive: {}", path.display()))),
+    }
+}
+
+fn normal_components(path: &Path) -> Vec<&OsStr> {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+// Symlink targets are resolved against the link's own directory and may
+// point anywhere inside the archive root, but never above it. The targets
+// are resolved by their text alone, which only holds while no step goes
+// through another symlink, so chains like `a -> b/..` with `b` a link are
+// refused as well.
+fn check_links(links: &[(PathBuf, PathBuf)]) -> Result<()> {
+    let link_paths: HashSet<Vec<&OsStr>> = links.iter().map(|(path, _)| normal_components(path)).collect();
+    for (path, target) in links {
+        let escapes = || Error::msg(format!("Refusing symlink that points outside the directory: {} -> {}", path.display(), target.display()));
+        let mut resolved = normal_components(path);
+        resolved.pop();
+        if resolved.is_empty() {
+            return Err(escapes());
+        }
+        if (1..=resolved.len()).any(|len| link_paths.contains(&resolved[..len])) {
+            return Err(Error::msg(format!("Refusing symlink below another symlink: {}", path.display())));
+        }
+        for component in target.components() {
+            if link_paths.contains(&resolved) {
+                return Err(Error::msg(format!("Refusing symlink that points through another symlink: {} -> {}", path.display(), target.display())));
+            }
+            match component {
+                Component::Normal(name) => resolved.push(name),
+                Component::CurDir => {}
+                Component::ParentDir if resolved.len() > 1 => {
+                    resolved.pop();
+                }
+                _ => return Err(escapes()),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn root_of(path: &Path) -> Option<&OsStr> {
+    path.components().find_map(|component| match component {
+        Component::Normal(name) => Some(name),
+        _ => None,
+    })
+}
+
+pub fn unpack(data: &[u8], output_dir: &Path) -> Result<PathBuf> {
+    let mut root = None;
+    let mut links = Vec::new();
+    for entry in Archive::new(data).entries()? {
+        let entry = entry?;
+        let path = entry.path()?;
+        check_path(&path)?;
+        if let Some(link_name) = entry.link_name()? {
+            match entry.header().entry_type().is_hard_link() {
+                true if root_of(&link_name) == root_of(&path) => check_path(&link_name)?,
+                true => return Err(Error::msg(format!("Refusing unsafe path in archive: {}", link_name.display()))),
+                false => links.push((path.to_path_buf(), link_name.to_path_buf())),
+            }
+        }
+        match root {
+            None => root = root_of(&path).map(|name| name.to_os_string()),
+            Some(ref name) if root_of(&path) != Some(name.as_os_str()) => {
+                return Err(Error::msg("Refusing archive with more than one top-level entry!"));
+            }
+            Some(_) => {}
+        }
+    }
+    check_links(&links)?;
+    let mut archive = Archive::new(data);
+    archive.set_preserve_permissions(true);
+    archive.set_preserve_mtime(true);
+    archive.set_overwrite(false);
+    archive.unpack(output_dir)?;
+    root.map(|name| output_dir.join(name)).ok_or(Error::msg("Empty archive!"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use tar::{Builder, EntryType, Header};
+
+    use super::{pack_dir, unpack};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rsa-tool-{}-{:016x}", name, rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // Writes the header names verbatim, since the tar crate refuses to
+    // build archives with unsafe paths itself.
+    fn raw_archive(entries: &[(&str, EntryType, &str)]) -> Vec<u8> {
+        let mut builder = Builder::new(Vec::new());
+        for (path, entry_type, link_name) in entries {
+            let mut header = Header::new_old();
+            header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+            header.as_old_mut().linkname[..link_name.len()].copy_from_slice(link_name.as_bytes());
+            header.set_entry_type(*entry_type);
+            header.set_mode(0o644);
+            header.set_size(0);
+            header.set_cksum();
+            builder.append(&header, &[][..]).unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    fn unpack_error(data: &[u8]) -> String {
+        let output_dir = temp_dir("unpack");
+        let err = unpack(data, &output_dir).unwrap_err().to_string();
+        fs::remove_dir_all(output_dir).unwrap();
+        err
+    }
+
+    #[test]
+    fn directories_round_trip() {
+        let source = temp_dir("source");
+        let dir = source.join("project");
+        fs::create_dir_all(dir.join("src/nested")).unwrap();
+        fs::write(dir.join("README"), b"readme").unwrap();
+        fs::write(dir.join("src/nested/main.rs"), b"fn main() {}").unwrap();
+        let output_dir = temp_dir("output");
+        let root = unpack(&pack_dir(&dir).unwrap(), &output_dir).unwrap();
+        assert_eq!(root, output_dir.join("project"));
+        assert_eq!(fs::read(root.join("README")).unwrap(), b"readme");
+        assert_eq!(fs::read(root.join("src/nested/main.rs")).unwrap(), b"fn main() {}");
+        fs::remove_dir_all(source).unwrap();
+        fs::remove_dir_all(output_dir).unwrap();
+    }
+
+    #[test]
+    fn parent_dir_entries_are_rejected() {
+        let data = raw_archive(&[("project/../../evil", EntryType::Regular, "")]);
+        assert!(unpack_error(&data).contains("unsafe path"));
+    }
+
+    #[test]
+    fn absolute_entries_are_rejected() {
+        let data = raw_archive(&[("/tmp/evil", EntryType::Regular, "")]);
+        assert!(unpack_error(&data).contains("unsafe path"));
+    }
+
+    #[test]
+    fn multiple_roots_are_rejected() {
+        let data = raw_archive(&[("project/a", EntryType::Regular, ""), ("other/b", EntryType::Regular, "")]);
+        assert!(unpack_error(&data).contains("more than one top-level entry"));
+    }
+
+    #[test]
+    fn escaping_symlinks_are_rejected() {
+        for target in ["../../etc/passwd", "/etc/passwd", "../.."] {
+            let data = raw_archive(&[("project/lib", EntryType::Symlink, target)]);
+            assert!(unpack_error(&data).contains("points outside the directory"), "{}", target);
+        }
+    }
+
+    // Each link stays inside the root on its own, but resolved through
+    // each other they end up above it.
+    #[test]
+    fn symlink_chains_are_rejected() {
+        let mut entries = vec![
+            ("project/x/", EntryType::Directory, ""),
+            ("project/x/y", EntryType::Symlink, ".."),
+            ("project/l1", EntryType::Symlink, "x/y"),
+            ("project/l2", EntryType::Symlink, "l1/.."),
+            ("project/l3", EntryType::Symlink, "l2/.."),
+        ];
+        assert!(unpack_error(&raw_archive(&entries)).contains("through another symlink"));
+        entries.reverse();
+        assert!(unpack_error(&raw_archive(&entries)).contains("through another symlink"));
+        let data = raw_archive(&[("project/lib", EntryType::Symlink, "x"), ("project/lib/evil", EntryType::Symlink, "..")]);
+        assert!(unpack_error(&data).contains("below another symlink"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_inside_the_root_round_trip() {
+        let source = temp_dir("source");
+        let dir = source.join("project");
+        fs::create_dir_all(dir.join("app")).unwrap();
+        fs::create_dir_all(dir.join("shared/lib")).unwrap();
+        fs::write(dir.join("shared/lib/code"), b"code").unwrap();
+        std::os::unix::fs::symlink("../shared/lib", dir.join("app/lib")).unwrap();
+        let output_dir = temp_dir("output");
+        let root = unpack(&pack_dir(&dir).unwrap(), &output_dir).unwrap();
+        assert_eq!(fs::read_link(root.join("app/lib")).unwrap(), PathBuf::from("../shared/lib"));
+        assert_eq!(fs::read(root.join("app/lib/code")).unwrap(), b"code");
+        std::os::unix::fs::symlink("lib/..", dir.join("app/chain")).unwrap();
+        assert!(pack_dir(&dir).unwrap_err().to_string().contains("through another symlink"));
+        fs::remove_file(dir.join("app/chain")).unwrap();
+        std::os::unix::fs::symlink("../../outside", dir.join("app/escape")).unwrap();
+        assert!(pack_dir(&dir).unwrap_err().to_string().contains("points outside the directory"));
+        fs::remove_dir_all(source).unwrap();
+        fs::remove_dir_all(output_dir).unwrap();
+    }
+}
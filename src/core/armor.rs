@@ -0,0 +1,137 @@
+use anyhow::{Error, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+const BEGIN: &str = "-----BEGIN RSA-TOOL MESSAGE-----";
+const END: &str = "-----END RSA-TOOL MESSAGE-----";
+const LINE_WIDTH: usize = 64;
+
+// CRC-24 as used by OpenPGP ASCII armor (RFC 4880, section 6.1).
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xB704CE;
+    for byte in data {
+        crc ^= (*byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= 0x1864CFB;
+            }
+        }
+    }
+    crc & 0xFFFFFF
+}
+
+pub fn armor(data: &[u8]) -> String {
+    let body = STANDARD.encode(data);
+    let mut text = String::new();
+    text.push_str(BEGIN);
+    text.push('\n');
+    for line in body.as_bytes().chunks(LINE_WIDTH) {
+        text.push_str(std::str::from_utf8(line).unwrap_or_default());
+        text.push('\n');
+    }
+    text.push('=');
+    text.push_str(&STANDARD.encode(&crc24(data).to_be_bytes()[1..]));
+    text.push('\n');
+    text.push_str(END);
+    text.push('\n');
+    text
+}
+
+pub fn is_armored(text: &str) -> bool {
+    text.trim_start().starts_with(BEGIN)
+}
+
+// Decodes as much of a message as is there, without the CRC check, so the
+// start of a large file is enough to read a container header.
+pub fn dearmor_prefix(text: &str) -> Option<Vec<u8>> {
+    let body: String = text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != BEGIN)
+        .skip(1)
+        .take_while(|line| *line != END && !line.starts_with('='))
+        .collect();
+    match body.is_ascii() {
+        true => STANDARD.decode(&body[..body.len() / 4 * 4]).ok(),
+        false => None,
+    }
+}
+
+pub fn dearmor(text: &str) -> Result<Vec<u8>> {
+    let mut lines = text
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != BEGIN);
+    if lines.next().is_none() {
+        return Err(Error::msg(format!("Missing \"{}\" line!", BEGIN)));
+    }
+    let mut body = String::new();
+    let mut checksum = None;
+    let mut is_closed = false;
+    for line in lines {
+        if line == END {
+            is_closed = true;
+            break;
+        }
+        match line.strip_prefix('=') {
+            Some(crc) => checksum = Some(crc.to_string()),
+            None => body.push_str(line),
+        }
+    }
+    if !is_closed {
+        return Err(Error::msg(format!("Missing \"{}\" line!", END)));
+    }
+    let data = STANDARD
+        .decode(body)
+        .map_err(|err| Error::msg(format!("Broken base64 in message: {}", err)))?;
+    let checksum = checksum.ok_or(Error::msg("Missing CRC line!"))?;
+    let checksum = STANDARD
+        .decode(checksum)
+        .map_err(|err| Error::msg(format!("Broken base64 in CRC line: {}", err)))?;
+    match checksum.as_slice() {
+        [a, b, c] if u32::from_be_bytes([0, *a, *b, *c]) == crc24(&data) => Ok(data),
+        _ => Err(Error::msg("CRC mismatch, the message is damaged!")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{armor, crc24, dearmor, is_armored};
+
+    #[test]
+    fn crc24_matches_the_openpgp_check_value() {
+        assert_eq!(crc24(b""), 0xB704CE);
+        assert_eq!(crc24(b"123456789"), 0x21CF02);
+    }
+
+    #[test]
+    fn armored_data_round_trips() {
+        for size in [0, 1, 2, 3, 47, 48, 49, 200] {
+            let data: Vec<u8> = (0..size).map(|index| (index * 7) as u8).collect();
+            let text = armor(&data);
+            assert!(is_armored(&text));
+            assert!(text.lines().all(|line| line.len() <= 64 || line.starts_with("-----")));
+            assert_eq!(dearmor(&text).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn surrounding_text_and_crlf_are_ignored() {
+        let text = format!("Here it is:\r\n\r\n{}\r\nthanks", armor(b"secret").replace('\n', "\r\n"));
+        assert_eq!(dearmor(&text).unwrap(), b"secret");
+    }
+
+    #[test]
+    fn damaged_messages_are_rejected() {
+        let text = armor(b"a short secret message");
+        let flipped = text.replacen("YSBzaG9y", "YSBzaG9z", 1);
+        assert_ne!(flipped, text);
+        assert!(dearmor(&flipped).unwrap_err().to_string().contains("CRC mismatch"));
+        let truncated: String = text.lines().take(2).map(|line| format!("{}\n", line)).collect();
+        assert!(dearmor(&truncated).unwrap_err().to_string().contains("END"));
+        let without_crc: String = text.lines().filter(|line| !line.starts_with('=')).map(|line| format!("{}\n", line)).collect();
+        assert!(dearmor(&without_crc).unwrap_err().to_string().contains("Missing CRC"));
+        assert!(dearmor("no armor here").unwrap_err().to_string().contains("BEGIN"));
+        assert!(dearmor(&text.replacen("YSBz", "YS!z", 1)).unwrap_err().to_string().contains("Broken base64"));
+    }
+}
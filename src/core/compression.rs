@@ -0,0 +1,252 @@
+use std::{borrow::Cow, fmt::{self, Display}, io::{self, Write}};
+
+use anyhow::{Error, Result};
+use flate2::write::{DeflateDecoder, DeflateEncoder};
+use serde::{Deserialize, Serialize};
+
+use super::problem::Problem;
+
+const ZSTD_LEVEL: i32 = 3;
+const ENTROPY_SAMPLE_SIZE: usize = 1024 * 1024;
+// Bits per byte above which data is treated as already compressed or encrypted.
+const ENTROPY_THRESHOLD: f64 = 7.5;
+// Deflate can't expand data more than 1032 times, zstd can. Anything declaring
+// more is refused, since the sender picks the size and the content key alike.
+const MAX_RATIO: u64 = 1032;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Zstd,
+    Deflate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionMode {
+    #[default]
+    Off,
+    Auto,
+    Zstd,
+    Deflate,
+}
+
+impl Compression {
+    pub(super) fn to_byte(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Zstd => 1,
+            Self::Deflate => 2,
+        }
+    }
+
+    pub(super) fn from_byte(byte: u8) -> Result<Self> {
+        match byte {
+            0 => Ok(Self::None),
+            1 => Ok(Self::Zstd),
+            2 => Ok(Self::Deflate),
+            _ => Err(Error::msg("Unknown compression!")),
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::None => "None",
+            Self::Zstd => "zstd",
+            Self::Deflate => "Deflate",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl CompressionMode {
+    pub const ALL: [Self; 4] = [Self::Off, Self::Auto, Self::Zstd, Self::Deflate];
+}
+
+// Shannon entropy of the byte histogram over the first megabyte.
+fn entropy(data: &[u8]) -> f64 {
+    let sample = &data[..data.len().min(ENTROPY_SAMPLE_SIZE)];
+    let mut counts = [0usize; 256];
+    sample.iter().for_each(|byte| counts[*byte as usize] += 1);
+    let len = sample.len() as f64;
+    counts
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let p = *count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn compress_with(compression: Compression, payload: &[u8]) -> Result<Vec<u8>> {
+    match compression {
+        Compression::None => Ok(payload.to_vec()),
+        Compression::Zstd => zstd::bulk::compress(payload, ZSTD_LEVEL).map_err(Error::new),
+        Compression::Deflate => {
+            let mut encoder = DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(payload)?;
+            encoder.finish().map_err(Error::new)
+        }
+    }
+}
+
+fn within_ratio(compressed_size: u64, size: u64) -> bool {
+    size <= compressed_size.saturating_mul(MAX_RATIO)
+}
+
+// Auto mode skips high-entropy input and keeps the original bytes whenever
+// compressing wouldn't make them smaller. Output that shrinks past the ratio
+// decompression accepts falls back to deflate, and failing that to none. An
+// explicitly chosen compression is never swapped for another, it fails.
+pub fn compress(mode: CompressionMode, payload: &[u8]) -> Result<(Compression, Cow<'_, [u8]>)> {
+    let compression = match mode {
+        CompressionMode::Off => return Ok((Compression::None, Cow::Borrowed(payload))),
+        CompressionMode::Auto if payload.is_empty() || entropy(payload) > ENTROPY_THRESHOLD => {
+            return Ok((Compression::None, Cow::Borrowed(payload)));
+        }
+        CompressionMode::Auto | CompressionMode::Zstd => Compression::Zstd,
+        CompressionMode::Deflate => Compression::Deflate,
+    };
+    let compressed = compress_with(compression, payload)?;
+    if mode != CompressionMode::Auto {
+        return match within_ratio(compressed.len() as u64, payload.len() as u64) {
+            true => Ok((compression, Cow::Owned(compressed))),
+            false => Err(Error::new(Problem::new("error-compression-ratio").arg("compression", compression))),
+        };
+    }
+    let (compression, compressed) = match within_ratio(compressed.len() as u64, payload.len() as u64) {
+        true => (compression, compressed),
+        false => (Compression::Deflate, compress_with(Compression::Deflate, payload)?),
+    };
+    match compressed.len() >= payload.len() || !within_ratio(compressed.len() as u64, payload.len() as u64) {
+        true => Ok((Compression::None, Cow::Borrowed(payload))),
+        false => Ok((compression, Cow::Owned(compressed))),
+    }
+}
+
+// Refuses anything past the declared size, so a forged header can't make
+// decompression fill the sink without bound.
+pub struct SizeLimit<'a> {
+    sink: &'a mut dyn Write,
+    remaining: u64,
+}
+
+impl Write for SizeLimit<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.len() as u64 > self.remaining {
+            return Err(io::Error::other("Decompressed size doesn't match the original!"));
+        }
+        let len = self.sink.write(buf)?;
+        self.remaining -= len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.sink.flush()
+    }
+}
+
+// Decompresses whatever is written to it on to the sink, so a payload can be
+// handled chunk by chunk. Uncompressed data passes through unchecked.
+pub enum Decompressor<'a> {
+    None(&'a mut dyn Write),
+    Zstd(zstd::stream::write::Decoder<'static, SizeLimit<'a>>),
+    Deflate(DeflateDecoder<SizeLimit<'a>>),
+}
+
+impl<'a> Decompressor<'a> {
+    pub fn new(compression: Compression, sink: &'a mut dyn Write, compressed_size: u64, expected_size: u64) -> Result<Self> {
+        if compression != Compression::None && !within_ratio(compressed_size, expected_size) {
+            return Err(Error::msg("The declared size is too large for the compressed data!"));
+        }
+        let limit = SizeLimit { sink, remaining: expected_size };
+        match compression {
+            Compression::None => Ok(Self::None(limit.sink)),
+            Compression::Zstd => Ok(Self::Zstd(zstd::stream::write::Decoder::new(limit)?)),
+            Compression::Deflate => Ok(Self::Deflate(DeflateDecoder::new(limit))),
+        }
+    }
+
+    pub fn finish(self) -> Result<()> {
+        let limit = match self {
+            Self::None(sink) => return sink.flush().map_err(Error::new),
+            Self::Zstd(mut decoder) => {
+                decoder.flush()?;
+                decoder.into_inner()
+            }
+            Self::Deflate(decoder) => decoder.finish()?,
+        };
+        match limit.remaining == 0 {
+            true => Ok(()),
+            false => Err(Error::msg("Decompressed size doesn't match the original!")),
+        }
+    }
+}
+
+impl Write for Decompressor<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::None(sink) => sink.write(buf),
+            Self::Zstd(decoder) => decoder.write(buf),
+            Self::Deflate(decoder) => decoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::None(sink) => sink.flush(),
+            Self::Zstd(decoder) => decoder.flush(),
+            Self::Deflate(decoder) => decoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use anyhow::Result;
+
+    use super::{compress, Compression, CompressionMode, Decompressor, Problem, MAX_RATIO};
+
+    fn decompress(compression: Compression, data: Vec<u8>, expected_size: u64) -> Result<Vec<u8>> {
+        let mut payload = Vec::new();
+        let mut decompressor = Decompressor::new(compression, &mut payload, data.len() as u64, expected_size)?;
+        decompressor.write_all(&data)?;
+        decompressor.finish()?;
+        Ok(payload)
+    }
+
+    #[test]
+    fn forged_sizes_are_rejected() {
+        let payload = vec![b'a'; 4096];
+        let (compression, compressed) = compress(CompressionMode::Zstd, &payload).unwrap();
+        assert_eq!(compression, Compression::Zstd);
+        let forged = compressed.len() as u64 * MAX_RATIO + 1;
+        let err = decompress(compression, compressed.to_vec(), forged).unwrap_err();
+        assert!(err.to_string().contains("too large"));
+        assert!(decompress(compression, compressed.to_vec(), payload.len() as u64 + 1).is_err());
+        assert_eq!(decompress(compression, compressed.to_vec(), payload.len() as u64).unwrap(), payload);
+    }
+
+    #[test]
+    fn extreme_ratios_fall_back_to_deflate_in_auto_mode() {
+        let payload = vec![0; 4 * 1024 * 1024];
+        let (compression, compressed) = compress(CompressionMode::Auto, &payload).unwrap();
+        assert_eq!(compression, Compression::Deflate);
+        assert_eq!(decompress(compression, compressed.to_vec(), payload.len() as u64).unwrap(), payload);
+    }
+
+    #[test]
+    fn explicit_zstd_is_never_swapped_for_another_compression() {
+        let payload = vec![0; 4 * 1024 * 1024];
+        let err = compress(CompressionMode::Zstd, &payload).unwrap_err();
+        assert_eq!(err.downcast_ref::<Problem>().unwrap().id, "error-compression-ratio");
+        let (compression, compressed) = compress(CompressionMode::Deflate, &payload).unwrap();
+        assert_eq!(compression, Compression::Deflate);
+        assert_eq!(decompress(compression, compressed.to_vec(), payload.len() as u64).unwrap(), payload);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{fmt::{self, Display}, path::{Path, PathBuf}};
+
+use anyhow::{Error, Result};
+use rsa::{pkcs1::{DecodeRsaPublicKey, EncodeRsaPublicKey}, pkcs8::LineEnding, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{fs, task};
+
+use super::output::{async_write_file, Overwrite};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contact {
+    pub name: String,
+    pub email: String,
+    pub public_key: RsaPublicKey,
+    pub fingerprint: String,
+}
+
+impl Contact {
+    pub fn new(name: String, email: String, public_key: RsaPublicKey) -> Self {
+        let fingerprint = fingerprint(&public_key);
+        Self { name, email, public_key, fingerprint }
+    }
+}
+
+impl Display for Contact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.email.is_empty() {
+            true => write!(f, "{}", self.name),
+            false => write!(f, "{} <{}>", self.name, self.email),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct ContactBook {
+    #[serde(default, rename = "contact")]
+    contacts: Vec<ContactRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ContactRecord {
+    name: String,
+    #[serde(default)]
+    email: String,
+    public_key: String,
+}
+
+pub fn fingerprint_bytes(public_key: &RsaPublicKey) -> [u8; 32] {
+    let der = public_key.to_pkcs1_der().map(|der| der.as_bytes().to_vec()).unwrap_or_default();
+    Sha256::digest(der).into()
+}
+
+pub fn fingerprint(public_key: &RsaPublicKey) -> String {
+    fingerprint_hex(&fingerprint_bytes(public_key))
+}
+
+pub fn fingerprint_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn config_dir() -> Result<PathBuf> {
+    dirs::config_dir()
+        .map(|dir| dir.join("rsa-tool"))
+        .ok_or(Error::msg("Failed to locate config directory!"))
+}
+
+fn contacts_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("contacts.toml"))
+}
+
+// A key that is already in the book keeps its entry, so importing it again
+// never duplicates it or overwrites the name and email given to it.
+pub fn import_contact(contacts: &mut Vec<Contact>, contact: Contact) {
+    if contacts.iter().all(|other| other.fingerprint != contact.fingerprint) {
+        contacts.push(contact);
+    }
+}
+
+pub async fn async_load_contacts() -> Result<Vec<Contact>> {
+    async_load_contacts_from(&contacts_path()?).await
+}
+
+async fn async_load_contacts_from(path: &Path) -> Result<Vec<Contact>> {
+    if !fs::try_exists(path).await? {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(path).await?;
+    task::spawn_blocking(move || {
+        let book: ContactBook = toml::from_str(&text)?;
+        book.contacts
+            .into_iter()
+            .map(|record| {
+                let public_key = RsaPublicKey::from_pkcs1_pem(&record.public_key)?;
+                Ok(Contact::new(record.name, record.email, public_key))
+            })
+            .collect()
+    }).await?
+}
+
+pub async fn async_save_contacts(contacts: Vec<Contact>) -> Result<()> {
+    async_save_contacts_to(contacts_path()?, contacts).await
+}
+
+async fn async_save_contacts_to(path: PathBuf, contacts: Vec<Contact>) -> Result<()> {
+    let text = task::spawn_blocking(move || {
+        let contacts = contacts
+            .into_iter()
+            .map(|contact| {
+                let public_key = contact.public_key.to_pkcs1_pem(LineEnding::LF)?;
+                Ok(ContactRecord { name: contact.name, email: contact.email, public_key })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        toml::to_string(&ContactBook { contacts }).map_err(Error::new)
+    }).await??;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).await?;
+    }
+    async_write_file(path, text.as_bytes(), Overwrite::Replace).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use rsa::{pkcs1::DecodeRsaPublicKey, RsaPublicKey};
+
+    use super::{async_load_contacts_from, async_save_contacts_to, import_contact, Contact};
+
+    const ALICE_KEY: &str = "
+-----BEGIN RSA PUBLIC KEY-----
+MIGJAoGBAJTsH0BGCuKsnoeRNZTNSp6OoY7XUrHdX6ZUcA7JI4OHX7OYgJZ9Jd2T
+UMFP0RjoD/0w/PXQPnNUn+8+yIvxGzdQ8EwQg1pZkUNF7xEmcnJEmel9nB+WILAM
+fR6pC717hL/MdbJGdQ3zC2/BfGRelK3g36aolJY/GYssmm9980HjAgMBAAE=
+-----END RSA PUBLIC KEY-----
+";
+    const BOB_KEY: &str = "
+-----BEGIN RSA PUBLIC KEY-----
+MIGJAoGBAMPqAzQTdPNnfopzJLYKTY1rHgQDrmASJxbnzGHigNNwO7w+I2vzNLri
+3Zy5L1OTsfSg+Zkb6MKgmTCKayQlcjMbbLYp4K49u3/n/LTGLTSPAGXmTtwnrXBF
+T1AgiqdGETU7YK16SZ4JGvvGmm7w/dqDkijeoBiZxMaBz3ZIc6lhAgMBAAE=
+-----END RSA PUBLIC KEY-----
+";
+
+    fn public_key(pem: &str) -> RsaPublicKey {
+        RsaPublicKey::from_pkcs1_pem(pem.trim()).unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rsa-tool-{}-{:016x}", name, rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn contacts_round_trip() {
+        let dir = temp_dir("contacts");
+        let path = dir.join("contacts.toml");
+        assert!(async_load_contacts_from(&path).await.unwrap().is_empty());
+        let contacts = vec![
+            Contact::new("Alice".into(), "alice@example.com".into(), public_key(ALICE_KEY)),
+            Contact::new("鲍勃".into(), String::new(), public_key(BOB_KEY)),
+        ];
+        async_save_contacts_to(path.clone(), contacts.clone()).await.unwrap();
+        assert_eq!(async_load_contacts_from(&path).await.unwrap(), contacts);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn importing_a_known_key_keeps_its_entry() {
+        let mut contacts = Vec::new();
+        import_contact(&mut contacts, Contact::new("Alice".into(), "alice@example.com".into(), public_key(ALICE_KEY)));
+        import_contact(&mut contacts, Contact::new("Bob".into(), String::new(), public_key(BOB_KEY)));
+        import_contact(&mut contacts, Contact::new("alice-laptop".into(), String::new(), public_key(ALICE_KEY)));
+        let names: Vec<&str> = contacts.iter().map(|contact| contact.name.as_str()).collect();
+        assert_eq!(names, ["Alice", "Bob"]);
+        assert_eq!(contacts[0].email, "alice@example.com");
+    }
+}
@@ -0,0 +1,195 @@
+use std::fmt::{self, Display};
+
+use anyhow::{Error, Result};
+use base64::{engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD}, Engine};
+use serde::{Deserialize, Serialize};
+
+use super::{armor::{armor, dearmor, dearmor_prefix, is_armored}, container::is_container};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputEncoding {
+    #[default]
+    Binary,
+    Base64,
+    Base64Url,
+    Hex,
+    Armor,
+}
+
+impl OutputEncoding {
+    pub const ALL: [Self; 5] = [Self::Binary, Self::Base64, Self::Base64Url, Self::Hex, Self::Armor];
+
+    pub fn encode(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Binary => data.to_vec(),
+            Self::Base64 => STANDARD.encode(data).into_bytes(),
+            Self::Base64Url => URL_SAFE_NO_PAD.encode(data).into_bytes(),
+            Self::Hex => data
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect::<String>()
+                .into_bytes(),
+            Self::Armor => armor(data).into_bytes(),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Binary => "encrypted",
+            Self::Base64 => "encrypted.b64",
+            Self::Base64Url => "encrypted.b64url",
+            Self::Hex => "encrypted.hex",
+            Self::Armor => "encrypted.asc",
+        }
+    }
+}
+
+impl Display for OutputEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Binary => "Binary",
+            Self::Base64 => "Base64",
+            Self::Base64Url => "Base64url",
+            Self::Hex => "Hex",
+            Self::Armor => "PEM armor",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(text.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+// Raw containers are recognized by their magic bytes. Text encodings are
+// only accepted when they decode to a container, anything else is passed
+// through untouched so legacy raw RSA ciphertexts keep working.
+pub fn decode_input(data: Vec<u8>) -> Result<Vec<u8>> {
+    decode_container(data).map(|(data, _)| data)
+}
+
+// Like `decode_input`, but also reports the encoding the container was found
+// in, or `None` when the data was passed through.
+pub fn decode_container(data: Vec<u8>) -> Result<(Vec<u8>, Option<OutputEncoding>)> {
+    if is_container(&data) {
+        return Ok((data, Some(OutputEncoding::Binary)));
+    }
+    let text = match std::str::from_utf8(&data) {
+        Ok(text) => text,
+        Err(_) => return Ok((data, None)),
+    };
+    if is_armored(text) {
+        return Ok((dearmor(text)?, Some(OutputEncoding::Armor)));
+    }
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    let candidates = [
+        (decode_hex(&compact), OutputEncoding::Hex),
+        (STANDARD.decode(&compact).ok(), OutputEncoding::Base64),
+        (URL_SAFE_NO_PAD.decode(compact.trim_end_matches('=')).ok(), OutputEncoding::Base64Url),
+    ];
+    match candidates.into_iter().find_map(|(decoded, encoding)| decoded.filter(|decoded| is_container(decoded)).map(|decoded| (decoded, encoding))) {
+        Some((decoded, encoding)) => Ok((decoded, Some(encoding))),
+        None if compact.is_empty() => Err(Error::msg("The input is empty!")),
+        None => Ok((data, None)),
+    }
+}
+
+// Decodes the start of an input that may be cut off anywhere. `None` means
+// it doesn't begin with a container in any of the encodings.
+pub fn decode_container_prefix(data: &[u8]) -> Option<Vec<u8>> {
+    if is_container(data) {
+        return Some(data.to_vec());
+    }
+    let text = std::str::from_utf8(data).ok()?;
+    if is_armored(text) {
+        return dearmor_prefix(text).filter(|decoded| is_container(decoded));
+    }
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if !compact.is_ascii() {
+        return None;
+    }
+    let groups = &compact[..compact.len() / 4 * 4];
+    let candidates = [
+        decode_hex(&compact[..compact.len() / 2 * 2]),
+        STANDARD.decode(groups).ok(),
+        URL_SAFE_NO_PAD.decode(groups).ok(),
+    ];
+    candidates.into_iter().flatten().find(|decoded| is_container(decoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_container, decode_container_prefix, decode_input, OutputEncoding};
+
+    fn container() -> Vec<u8> {
+        let mut data = b"RSATOOL\0\x01".to_vec();
+        data.extend((0..=255u8).rev());
+        data
+    }
+
+    #[test]
+    fn every_encoding_is_detected() {
+        for encoding in OutputEncoding::ALL {
+            let encoded = encoding.encode(&container());
+            let (decoded, detected) = decode_container(encoded.clone()).unwrap();
+            assert_eq!(decoded, container(), "{}", encoding);
+            assert_eq!(detected, Some(encoding), "{}", encoding);
+        }
+    }
+
+    // Short inputs can be valid base64 and base64url at once, then the
+    // reported encoding must still write the same bytes back.
+    #[test]
+    fn ambiguous_base64_re_encodes_identically() {
+        let data = b"RSATOOL\0\x01abc".to_vec();
+        let encoded = OutputEncoding::Base64Url.encode(&data);
+        let (decoded, detected) = decode_container(encoded.clone()).unwrap();
+        assert_eq!(decoded, data);
+        assert_eq!(detected.unwrap().encode(&decoded), encoded);
+    }
+
+    #[test]
+    fn wrapped_text_is_accepted() {
+        let encoded = String::from_utf8(OutputEncoding::Base64.encode(&container())).unwrap();
+        let wrapped: Vec<&str> = encoded.as_bytes().chunks(76).map(|line| std::str::from_utf8(line).unwrap()).collect();
+        assert_eq!(decode_input(format!("  {}\r\n", wrapped.join("\r\n")).into_bytes()).unwrap(), container());
+        let hex = String::from_utf8(OutputEncoding::Hex.encode(&container())).unwrap().to_uppercase();
+        assert_eq!(decode_input(hex.into_bytes()).unwrap(), container());
+    }
+
+    #[test]
+    fn other_input_is_passed_through() {
+        let legacy = vec![0xff, 0x00, 0x81, 0x7f];
+        assert_eq!(decode_container(legacy.clone()).unwrap(), (legacy, None));
+        let text = b"aGVsbG8gd29ybGQ=".to_vec();
+        assert_eq!(decode_container(text.clone()).unwrap(), (text, None));
+    }
+
+    #[test]
+    fn malformed_input_is_rejected() {
+        assert!(decode_input(b" \n\t".to_vec()).is_err());
+        let armored = String::from_utf8(OutputEncoding::Armor.encode(&container())).unwrap();
+        let truncated = armored.lines().take(3).collect::<Vec<_>>().join("\n");
+        assert!(decode_input(truncated.into_bytes()).is_err());
+    }
+
+    #[test]
+    fn truncated_inputs_decode_to_a_prefix() {
+        for encoding in OutputEncoding::ALL {
+            let encoded = encoding.encode(&container());
+            for len in [80, 81, 82, 83, 200, encoded.len()] {
+                let prefix = decode_container_prefix(&encoded[..len]).unwrap_or_else(|| panic!("{} at {}", encoding, len));
+                assert!(prefix.len() > 9 && container().starts_with(&prefix), "{} at {}", encoding, len);
+            }
+        }
+        assert_eq!(decode_container_prefix(b"aGVsbG8gd29ybGQ="), None);
+        assert_eq!(decode_container_prefix(&[0xff, 0x00, 0x81]), None);
+    }
+}
@@ -1,16 +1,53 @@
 mod lib;
+mod contacts;
+mod compression;
+mod container;
+mod archive;
+mod armor;
+mod encoding;
+mod keys;
+mod settings;
+mod output;
+mod shred;
+mod problem;
 
 pub use lib::{
-    async_encrypt,
-    async_decrypt,
+    Cancelled,
     async_generate_priv_key_from_bits,
     async_pick_file,
-    async_save_encrypted_file,
-    async_save_decrypted_file,
-    async_get_data,
+    async_pick_files,
+    async_pick_output_dir,
+    async_encrypt_file_to,
+    async_decrypt_file_to,
+    async_encrypt_text,
+    async_decrypt_text,
+    async_inspect_file,
+    async_rewrap_file,
+    async_benchmark_aead,
+    async_find_stored_private_key,
+    async_get_key,
     async_to_priv_key,
     async_to_pub_key,
     async_priv_key_to_pub_key,
+    async_pick_private_pem_file,
+    async_pick_public_pem_file,
     async_save_private_pem_file,
     async_save_public_pem_file,
-};
\ No newline at end of file
+};
+pub use contacts::{
+    Contact,
+    config_dir,
+    fingerprint,
+    import_contact,
+    async_load_contacts,
+    async_save_contacts,
+};
+
+pub use compression::{Compression, CompressionMode};
+pub use container::{AeadAlgorithm, Inspection, KeyTransport, PayloadKind};
+pub use encoding::OutputEncoding;
+pub use shred::async_shred_verified;
+pub use problem::Problem;
+pub use output::{AlreadyExists, Overwrite, async_exposed_mode, async_restrict_to_owner};
+pub use keys::{DetectedKey, KeyDiagnostic, KeyFormat};
+pub use settings::{Settings, ThemeMode, Language, KEY_SIZES, EXPONENTS, load_settings, async_save_settings};
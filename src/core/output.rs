@@ -0,0 +1,276 @@
+use std::{fmt::{self, Display}, path::{Path, PathBuf}};
+
+use anyhow::{Error, Result};
+use rand::prelude::*;
+use tokio::{fs::{self, File}, io::AsyncWriteExt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overwrite {
+    #[default]
+    Ask,
+    Replace,
+    Rename,
+}
+
+#[derive(Debug)]
+pub struct AlreadyExists(pub PathBuf);
+
+impl Display for AlreadyExists {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} already exists!", self.0.display())
+    }
+}
+
+impl std::error::Error for AlreadyExists {}
+
+// "name.encrypted" becomes "name (1).encrypted", "name (2).encrypted", ...
+fn numbered_path(path: &Path, number: usize) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(extension) => format!("{} ({}).{}", stem, number, extension.to_string_lossy()),
+        None => format!("{} ({})", stem, number),
+    };
+    path.with_file_name(name)
+}
+
+pub async fn async_resolve_output(path: PathBuf, overwrite: Overwrite) -> Result<PathBuf> {
+    if !fs::try_exists(&path).await? {
+        return Ok(path);
+    }
+    match overwrite {
+        Overwrite::Ask => Err(Error::new(AlreadyExists(path))),
+        Overwrite::Replace => Ok(path),
+        Overwrite::Rename => {
+            for number in 1.. {
+                let candidate = numbered_path(&path, number);
+                if !fs::try_exists(&candidate).await? {
+                    return Ok(candidate);
+                }
+            }
+            unreachable!()
+        }
+    }
+}
+
+// A hidden sibling of the target, so the final rename never crosses file systems.
+pub fn temporary_path(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{:016x}.tmp", name, thread_rng().gen::<u64>()))
+}
+
+async fn async_sync_dir(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        File::open(dir).await?.sync_all().await?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+async fn async_write_temporary(temporary: &Path, data: &[u8], mode: Option<u32>) -> Result<()> {
+    let mut options = File::options();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        options.mode(mode);
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    let mut file = options.open(temporary).await?;
+    file.write_all(data).await?;
+    file.sync_all().await?;
+    Ok(())
+}
+
+pub async fn async_write_file(path: PathBuf, data: &[u8], overwrite: Overwrite) -> Result<PathBuf> {
+    async_write(path, data, overwrite, None).await
+}
+
+// Only the owner may read it; the mode is set at creation, so the secret is
+// never briefly exposed under the default umask.
+pub async fn async_write_secret_file(path: PathBuf, data: &[u8], overwrite: Overwrite) -> Result<PathBuf> {
+    async_write(path, data, overwrite, Some(0o600)).await
+}
+
+// Replaces a file that is being updated in place, such as a container whose
+// recipients changed. The new contents keep the old permissions and go
+// through a temporary file, so the file is never half written.
+pub async fn async_replace_file(path: PathBuf, data: &[u8]) -> Result<PathBuf> {
+    #[cfg(unix)]
+    let mode = Some(std::os::unix::fs::PermissionsExt::mode(&fs::metadata(&path).await?.permissions()) & 0o777);
+    #[cfg(not(unix))]
+    let mode = None;
+    async_write(path, data, Overwrite::Replace, mode).await
+}
+
+// Writes to a temporary file, fsyncs it and renames it into place, so the
+// target is either left untouched or fully written.
+async fn async_write(path: PathBuf, data: &[u8], overwrite: Overwrite, mode: Option<u32>) -> Result<PathBuf> {
+    let path = async_resolve_output(path, overwrite).await?;
+    let temporary = temporary_path(&path);
+    if let Err(err) = async_write_temporary(&temporary, data, mode).await {
+        let _ = fs::remove_file(&temporary).await;
+        return Err(err);
+    }
+    if let Err(err) = fs::rename(&temporary, &path).await {
+        let _ = fs::remove_file(&temporary).await;
+        return Err(Error::new(err));
+    }
+    async_sync_dir(&path).await?;
+    Ok(path)
+}
+
+// Moves a finished file or directory into place. Since rename can't swap
+// non-empty directories, a directory being replaced is renamed aside first
+// and only deleted once the new one is in place, or put back if that fails.
+pub async fn async_move_into_place(source: &Path, path: PathBuf, overwrite: Overwrite) -> Result<PathBuf> {
+    let path = async_resolve_output(path, overwrite).await?;
+    let replaced = match fs::symlink_metadata(&path).await.is_ok_and(|metadata| metadata.is_dir()) && fs::symlink_metadata(source).await?.is_dir() {
+        true => {
+            let aside = temporary_path(&path);
+            fs::rename(&path, &aside).await?;
+            Some(aside)
+        }
+        false => None,
+    };
+    if let Err(err) = fs::rename(source, &path).await {
+        if let Some(aside) = &replaced {
+            let _ = fs::rename(aside, &path).await;
+        }
+        return Err(Error::new(err));
+    }
+    async_sync_dir(&path).await?;
+    if let Some(aside) = replaced {
+        let _ = fs::remove_dir_all(aside).await;
+    }
+    Ok(path)
+}
+
+// Group and other permission bits of a file, or `None` when only the owner
+// can access it. Always `None` where Unix permissions don't apply.
+pub async fn async_exposed_mode(path: PathBuf) -> Result<Option<u32>> {
+    #[cfg(unix)]
+    {
+        let mode = std::os::unix::fs::PermissionsExt::mode(&fs::metadata(path).await?.permissions()) & 0o777;
+        Ok((mode & 0o077 != 0).then_some(mode))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        Ok(None)
+    }
+}
+
+pub async fn async_restrict_to_owner(path: PathBuf) -> Result<()> {
+    #[cfg(unix)]
+    fs::set_permissions(&path, std::os::unix::fs::PermissionsExt::from_mode(0o600)).await?;
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::{Path, PathBuf}};
+
+    use super::{async_exposed_mode, async_move_into_place, async_replace_file, async_resolve_output, async_restrict_to_owner, async_write_file, async_write_secret_file, numbered_path, AlreadyExists, Overwrite};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rsa-tool-{}-{:016x}", name, rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn file_names(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn numbered_paths_keep_the_extension() {
+        assert_eq!(numbered_path(Path::new("/out/report.txt.encrypted"), 1), Path::new("/out/report.txt (1).encrypted"));
+        assert_eq!(numbered_path(Path::new("/out/project"), 2), Path::new("/out/project (2)"));
+    }
+
+    #[tokio::test]
+    async fn existing_outputs_follow_the_overwrite_choice() {
+        let dir = temp_dir("output");
+        let path = dir.join("report.encrypted");
+        assert_eq!(async_resolve_output(path.clone(), Overwrite::Ask).await.unwrap(), path);
+        fs::write(&path, b"old").unwrap();
+        let err = async_resolve_output(path.clone(), Overwrite::Ask).await.unwrap_err();
+        assert_eq!(err.downcast::<AlreadyExists>().unwrap().0, path);
+        assert_eq!(async_resolve_output(path.clone(), Overwrite::Replace).await.unwrap(), path);
+        assert_eq!(async_resolve_output(path.clone(), Overwrite::Rename).await.unwrap(), dir.join("report (1).encrypted"));
+        fs::write(dir.join("report (1).encrypted"), b"old").unwrap();
+        assert_eq!(async_resolve_output(path.clone(), Overwrite::Rename).await.unwrap(), dir.join("report (2).encrypted"));
+        assert_eq!(async_write_file(path.clone(), b"new", Overwrite::Replace).await.unwrap(), path);
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    // A directory in the way makes the final rename fail after the
+    // temporary file was written, which must not be left behind.
+    #[tokio::test]
+    async fn failed_writes_leave_no_temporary_file() {
+        let dir = temp_dir("output");
+        fs::create_dir(dir.join("report")).unwrap();
+        fs::write(dir.join("report/inner"), b"keep").unwrap();
+        assert!(async_write_file(dir.join("report"), b"new", Overwrite::Replace).await.is_err());
+        assert!(async_write_file(dir.join("missing/report"), b"new", Overwrite::Replace).await.is_err());
+        assert_eq!(file_names(&dir), ["report"]);
+        assert_eq!(fs::read(dir.join("report/inner")).unwrap(), b"keep");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn replaced_files_keep_their_permissions() {
+        let dir = temp_dir("output");
+        let path = dir.join("archive.encrypted");
+        fs::write(&path, b"old").unwrap();
+        #[cfg(unix)]
+        fs::set_permissions(&path, std::os::unix::fs::PermissionsExt::from_mode(0o640)).unwrap();
+        assert_eq!(async_replace_file(path.clone(), b"new").await.unwrap(), path);
+        assert_eq!(fs::read(&path).unwrap(), b"new");
+        #[cfg(unix)]
+        assert_eq!(std::os::unix::fs::PermissionsExt::mode(&fs::metadata(&path).unwrap().permissions()) & 0o777, 0o640);
+        assert_eq!(file_names(&dir), ["archive.encrypted"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    // Moving a directory into itself is one rename that always fails, and
+    // only after the old directory was renamed aside.
+    #[tokio::test]
+    async fn replaced_directories_survive_a_failed_move() {
+        let dir = temp_dir("output");
+        let path = dir.join("project");
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("old"), b"old").unwrap();
+        assert!(async_move_into_place(&dir, path.clone(), Overwrite::Replace).await.is_err());
+        assert_eq!(file_names(&dir), ["project"]);
+        assert_eq!(file_names(&path), ["old"]);
+        fs::create_dir(dir.join("staging")).unwrap();
+        fs::write(dir.join("staging/new"), b"new").unwrap();
+        assert_eq!(async_move_into_place(&dir.join("staging"), path.clone(), Overwrite::Replace).await.unwrap(), path);
+        assert_eq!(file_names(&dir), ["project"]);
+        assert_eq!(file_names(&path), ["new"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn secret_files_are_only_readable_by_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = temp_dir("output");
+        let path = async_write_secret_file(dir.join("private.pem"), b"secret", Overwrite::Ask).await.unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(async_exposed_mode(path.clone()).await.unwrap(), None);
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert_eq!(async_exposed_mode(path.clone()).await.unwrap(), Some(0o644));
+        async_restrict_to_owner(path.clone()).await.unwrap();
+        assert_eq!(async_exposed_mode(path).await.unwrap(), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
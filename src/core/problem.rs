@@ -0,0 +1,32 @@
+use std::fmt::{self, Display};
+
+// An error meant for the user. The core only names the catalog message and
+// its arguments, the GUI translates them into the selected language.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub id: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl Problem {
+    pub fn new(id: &'static str) -> Self {
+        Self { id, args: Vec::new() }
+    }
+
+    pub fn arg(mut self, name: &'static str, value: impl Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.id)?;
+        for (name, value) in &self.args {
+            write!(f, " {}={:?}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Problem {}
@@ -0,0 +1,174 @@
+use std::{fmt::{self, Display}, fs, path::{Path, PathBuf}};
+
+use anyhow::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{compression::CompressionMode, contacts::config_dir, container::{AeadAlgorithm, KeyTransport, SealOptions}, encoding::OutputEncoding, output::{async_write_file, Overwrite}};
+
+pub const KEY_SIZES: [usize; 4] = [2048, 3072, 4096, 8192];
+pub const EXPONENTS: [u64; 3] = [65537, 17, 3];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    #[default]
+    System,
+    Light,
+    Dark,
+    HighContrast,
+}
+
+impl ThemeMode {
+    pub const ALL: [Self; 4] = [Self::System, Self::Light, Self::Dark, Self::HighContrast];
+}
+
+impl Display for ThemeMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::System => "System",
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+            Self::HighContrast => "High contrast",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    #[default]
+    System,
+    English,
+    Chinese,
+}
+
+impl Language {
+    pub const ALL: [Self; 3] = [Self::System, Self::English, Self::Chinese];
+}
+
+impl Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::System => "System",
+            Self::English => "English",
+            Self::Chinese => "中文",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub key_size: usize,
+    pub exponent: u64,
+    pub key_transport: KeyTransport,
+    pub aead: AeadAlgorithm,
+    pub compression: CompressionMode,
+    pub output_encoding: OutputEncoding,
+    pub output_dir: Option<PathBuf>,
+    pub theme: ThemeMode,
+    pub language: Language,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            key_size: 2048,
+            exponent: 65537,
+            key_transport: KeyTransport::Pkcs1v15,
+            aead: AeadAlgorithm::Aes256Gcm,
+            compression: CompressionMode::Off,
+            output_encoding: OutputEncoding::Binary,
+            output_dir: None,
+            theme: ThemeMode::System,
+            language: Language::System,
+        }
+    }
+}
+
+impl Settings {
+    pub fn seal_options(&self) -> SealOptions {
+        SealOptions { key_transport: self.key_transport, aead: self.aead, compression: self.compression }
+    }
+}
+
+fn settings_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("settings.toml"))
+}
+
+// Read once before the window opens, so the first frame already uses the
+// saved theme and defaults.
+pub fn load_settings() -> Result<Settings> {
+    load_settings_from(&settings_path()?)
+}
+
+fn load_settings_from(path: &Path) -> Result<Settings> {
+    if !path.exists() {
+        return Ok(Settings::default());
+    }
+    let text = fs::read_to_string(path)?;
+    toml::from_str(&text).map_err(Error::new)
+}
+
+pub async fn async_save_settings(settings: Settings) -> Result<()> {
+    async_save_settings_to(settings_path()?, settings).await
+}
+
+async fn async_save_settings_to(path: PathBuf, settings: Settings) -> Result<()> {
+    let text = toml::to_string(&settings)?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    async_write_file(path, text.as_bytes(), Overwrite::Replace).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::{async_save_settings_to, load_settings_from, AeadAlgorithm, CompressionMode, KeyTransport, Language, OutputEncoding, Settings, ThemeMode};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rsa-tool-{}-{:016x}", name, rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn settings_round_trip() {
+        let dir = temp_dir("settings");
+        let path = dir.join("settings.toml");
+        let settings = Settings {
+            key_size: 4096,
+            exponent: 17,
+            key_transport: KeyTransport::RsaKemHkdfSha256,
+            aead: AeadAlgorithm::XChaCha20Poly1305,
+            compression: CompressionMode::Auto,
+            output_encoding: OutputEncoding::Armor,
+            output_dir: Some(dir.join("out")),
+            theme: ThemeMode::HighContrast,
+            language: Language::Chinese,
+        };
+        async_save_settings_to(path.clone(), settings.clone()).await.unwrap();
+        assert_eq!(load_settings_from(&path).unwrap(), settings);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    // Settings files from older versions lack the newer fields, which take
+    // their defaults while the rest is kept.
+    #[test]
+    fn missing_and_partial_settings_fall_back_to_defaults() {
+        let dir = temp_dir("settings");
+        let path = dir.join("settings.toml");
+        assert_eq!(load_settings_from(&path).unwrap(), Settings::default());
+        fs::write(&path, "key_size = 3072\ntheme = \"dark\"\n").unwrap();
+        let expected = Settings { key_size: 3072, theme: ThemeMode::Dark, ..Settings::default() };
+        assert_eq!(load_settings_from(&path).unwrap(), expected);
+        fs::write(&path, "key_size = \"large\"\n").unwrap();
+        assert!(load_settings_from(&path).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
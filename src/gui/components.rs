@@ -1,48 +1,124 @@
+use iced::widget::{text, Text};
+
+// Translated strings may contain CJK text, which needs advanced shaping to
+// pick up a fallback font.
+fn label<'a>(content: impl text::IntoFragment<'a>) -> Text<'a> {
+    text(content).shaping(text::Shaping::Advanced)
+}
+
 pub mod setting {
-    use iced::{alignment::{Horizontal, Vertical}, widget::{container, text, text_editor, Column, Row, Scrollable}, Color, Element};
-    use crate::gui::{message::Message, state::State};
+    use iced::{alignment::{Horizontal, Vertical}, widget::{button, container, text, text_editor, text_input, Column, Row, Scrollable}, Element, Length};
+    use crate::{core::fingerprint, gui::{keymap::Action, message::Message, state::State, theme}};
+    use super::{label, navigation::shortcut_label};
 
-    fn private_key_input(state: &State) -> Element<Message> {
-        let title = text("Private key or RSA bit(default: 2048)");
+    fn private_key_input(state: &State) -> Element<'_, Message> {
+        let title = label(state.tr_args("private-key-title", &[("bits", state.get_settings().key_size.into())]));
         let text_editor = text_editor(state.get_priv_key_text_content())
             .on_action(Message::OnPrivateKeyAction)
             .size(16)
-            .height(550);
-        let private_key_box = Scrollable::new(text_editor).spacing(24);
-        let status  = match state.get_private_key().is_some() {
-            true => ("Private key is ready!", Color::from_rgb(0., 20., 0.)),
-            false => ("Private key isn't ready...", Color::from_rgb(20., 0., 0.)),
+            .height(Length::Fill);
+        let status = match state.get_private_key_format() {
+            Some(format) => state.tr_args("private-key-ready", &[("format", format.to_string().into())]),
+            None => state.tr("private-key-not-ready"),
+        };
+        let style = match (state.get_private_key_format(), state.get_private_key_diagnostic()) {
+            (Some(_), _) => theme::success,
+            (None, Some(_)) => theme::error,
+            (None, None) => theme::warning,
         };
-        let bottom_text = text(status.0).center().color(status.1).size(20);
+        let bottom_text = label(status).center().style(style).size(20);
+        let passphrase = text_input(&state.tr("passphrase-placeholder"), state.get_passphrase_text())
+            .secure(true)
+            .on_input(Message::SetPassphrase)
+            .size(16);
+        let diagnostic = state
+            .get_private_key_diagnostic()
+            .map(|diagnostic| text(diagnostic).style(theme::error).size(14));
+        let exposed = state.get_exposed_key_file().map(|(path, mode)| {
+            let warning = state.tr_args("private-key-exposed", &[("path", path.display().to_string().into()), ("mode", format!("{:o}", mode).into())]);
+            Row::new()
+                .spacing(10)
+                .align_y(Vertical::Center)
+                .push(label(warning).style(theme::warning).size(14).width(Length::Fill))
+                .push(button(label(state.tr("restrict-permissions")).size(14)).on_press(Message::RestrictKeyFile))
+        });
         let column = Column::new()
             .align_x(Horizontal::Center)
             .push(title)
-            .push(private_key_box)
-            .push(bottom_text);
+            .push(text_editor)
+            .push(passphrase)
+            .push(bottom_text)
+            .push_maybe(diagnostic)
+            .push_maybe(exposed);
         column.into()
     }
     
-    fn public_key_input(state: &State) -> Element<Message> {
-        let title = text("Public key");
+    fn public_key_input(state: &State) -> Element<'_, Message> {
+        let title = label(state.tr("public-key-title"));
         let text_editor = text_editor(state.get_pub_key_text_content())
             .on_action(Message::OnPublicKeyAction)
             .size(16)
-            .height(550);
-        let public_key_box = Scrollable::new(text_editor).spacing(24);
-        let status  = match state.get_public_key().is_some() {
-            true => ("Public key is ready!", Color::from_rgb(0., 20., 0.)),
-            false => ("Public key isn't ready...", Color::from_rgb(20., 0., 0.)),
+            .height(Length::Fill);
+        let status = match state.get_public_key_format() {
+            Some(format) => state.tr_args("public-key-ready", &[("format", format.to_string().into())]),
+            None => state.tr("public-key-not-ready"),
         };
-        let bottom_text = text(status.0).center().color(status.1).size(20);
+        let style = match (state.get_public_key_format(), state.get_public_key_diagnostic()) {
+            (Some(_), _) => theme::success,
+            (None, Some(_)) => theme::error,
+            (None, None) => theme::warning,
+        };
+        let bottom_text = label(status).center().style(style).size(20);
+        let copy_key = button(label(state.tr("copy")).size(14))
+            .on_press_maybe(state.get_public_key().map(|_| Message::CopyToClipboard(state.get_pub_key_text_content().text())));
+        let copy_fingerprint = button(label(state.tr("copy-fingerprint")).size(14))
+            .on_press_maybe(state.get_public_key().map(|public_key| Message::CopyToClipboard(fingerprint(&public_key))));
+        let paste_key = button(label(state.tr("paste")).size(14)).on_press(Message::PastePublicKey);
+        let buttons = Row::new().spacing(10).push(copy_key).push(copy_fingerprint).push(paste_key);
+        let diagnostic = state
+            .get_public_key_diagnostic()
+            .map(|diagnostic| text(diagnostic).style(theme::error).size(14));
         let column = Column::new()
             .align_x(Horizontal::Center)
             .push(title)
-            .push(public_key_box)
-            .push(bottom_text);
+            .push(text_editor)
+            .push(bottom_text)
+            .push_maybe(diagnostic)
+            .push(buttons);
         column.into()
     }
-    
-    pub fn view(state: &State) -> Element<Message> {
+
+    fn contact_book(state: &State) -> Element<'_, Message> {
+        let title = label(state.tr("contacts-title"));
+        let contacts = state
+            .get_contacts()
+            .iter()
+            .enumerate()
+            .fold(Column::new().spacing(10), |column, (index, contact)| {
+                let name = text_input(&state.tr("contact-name"), &contact.name)
+                    .on_input(move |name| Message::SetContactName(index, name))
+                    .size(16);
+                let email = text_input(&state.tr("contact-email"), &contact.email)
+                    .on_input(move |email| Message::SetContactEmail(index, email))
+                    .size(16);
+                let fingerprint = text(&contact.fingerprint).size(12);
+                let copy = button(label(state.tr("copy-fingerprint")).size(14))
+                    .on_press(Message::CopyToClipboard(contact.fingerprint.clone()));
+                let remove = button(label(state.tr("remove")).size(14))
+                    .on_press(Message::RemoveContact(contact.fingerprint.clone()));
+                let buttons = Row::new().spacing(10).push(copy).push(remove);
+                column.push(Column::new().spacing(4).push(name).push(email).push(fingerprint).push(buttons))
+            });
+        let contact_box = Scrollable::new(contacts).height(Length::Fill);
+        let column = Column::new()
+            .align_x(Horizontal::Center)
+            .width(300)
+            .push(title)
+            .push(contact_box);
+        column.into()
+    }
+
+    pub fn view(state: &State) -> Element<'_, Message> {
         let layer = Row::new()
             .align_y(Vertical::Center)
             .push(
@@ -50,47 +126,159 @@ pub mod setting {
             )
             .push(
                 public_key_input(state),
+            )
+            .push(
+                contact_book(state),
             );
-        let bottom_text = text("\nCtrl+G -> Generate both keys\nCtrl+S -> Save available Keys").size(30);
-        let layer = Column::new().push(layer).push(bottom_text).align_x(Horizontal::Center);
+        let savable = state.get_private_key().is_some() || state.get_public_key().is_some();
+        let toolbar = Row::new()
+            .spacing(10)
+            .push(button(label(shortcut_label(state, Action::GenerateKeys))).on_press(Message::GenerateKeys))
+            .push(button(label(shortcut_label(state, Action::SaveKeys))).on_press_maybe(savable.then_some(Message::SaveKeys)));
+        let layer = Column::new().spacing(10).push(toolbar).push(layer).align_x(Horizontal::Center);
         let container = container(layer)
             .align_x(Horizontal::Center)
             .align_y(Vertical::Center)
+            .height(Length::Fill)
             .padding(10);
         container.into()
     }
 }
 
 pub mod calculate {
-    use std::path::PathBuf;
+    use iced::{alignment::{Horizontal, Vertical}, widget::{button, checkbox, container, pick_list, text, text_editor, Column, Row, Scrollable}, Element, Length};
+    use crate::{core::OutputEncoding, gui::{keymap::Action, message::Message, state::{Job, JobStatus, State, ViewState}, theme}};
+    use super::{label, navigation::{shortcut_label, shortcut_label_with}};
 
-    use iced::{alignment::Horizontal, widget::{container, text, Column}, Element, Length};
-    use crate::gui::{message::Message, state::{State, ViewState}};
+    fn text_panel(state: &State) -> Element<'_, Message> {
+        let (placeholder, run_text) = match state.get_view_state() {
+            ViewState::Encrypting => (state.tr("plaintext-placeholder"), state.tr("encrypt-text")),
+            _ => ("-----BEGIN RSA-TOOL MESSAGE-----".to_string(), state.tr("decrypt-text")),
+        };
+        let input = text_editor(state.get_text_input_content())
+            .placeholder(placeholder)
+            .on_action(Message::OnTextInputAction)
+            .size(14)
+            .height(Length::Fill);
+        let output = text_editor(state.get_text_output_content())
+            .on_action(Message::OnTextOutputAction)
+            .size(14)
+            .height(Length::Fill);
+        let actions = Column::new()
+            .spacing(10)
+            .align_x(Horizontal::Center)
+            .push(button(label(state.tr("paste"))).on_press(Message::PasteTextInput))
+            .push(button(label(run_text)).on_press(Message::RunText))
+            .push(button(label(state.tr("copy"))).on_press(Message::CopyTextOutput));
+        let actions = match state.get_view_state() {
+            ViewState::Decrypting => actions.push(button(label(shortcut_label_with(state, state.tr("from-clipboard"), Action::DecryptFromClipboard))).on_press(Message::DecryptFromClipboard)),
+            _ => actions,
+        };
+        Row::new()
+            .spacing(10)
+            .height(Length::FillPortion(1))
+            .align_y(Vertical::Center)
+            .push(input)
+            .push(actions)
+            .push(output)
+            .into()
+    }
 
-    pub fn view(state: &State) -> Element<Message> {
-        let default_text = match state.get_view_state() {
-            ViewState::Encrypting => "RSA tool - Encrypt",
-            ViewState::Decrypting => "RSA tool - Decrypt",
-            _ => panic!()
+    fn job_row<'a>(state: &State, job: &'a Job) -> Element<'a, Message> {
+        let status = match &job.status {
+            JobStatus::Pending => state.tr("job-queued"),
+            JobStatus::Running => state.tr("job-running"),
+            JobStatus::Done(output_path, None) => state.tr_args("job-done", &[("path", output_path.display().to_string().into())]),
+            JobStatus::Done(output_path, Some(warning)) => state.tr_args("job-done-warning", &[("path", output_path.display().to_string().into()), ("warning", warning.as_str().into())]),
+            JobStatus::Failed(err) => state.tr_args("job-failed", &[("error", err.as_str().into())]),
+            JobStatus::Conflict(output_path) => state.tr_args("job-conflict", &[("path", output_path.display().to_string().into())]),
         };
-        let f =|path: PathBuf| {
-            path
-                .to_str()
-                .map(|text| text.to_string())
-                .unwrap_or(default_text.to_string())
+        let style = match &job.status {
+            JobStatus::Done(_, None) => theme::success,
+            JobStatus::Done(_, Some(_)) => theme::warning,
+            JobStatus::Failed(_) => theme::error,
+            JobStatus::Conflict(_) => theme::warning,
+            _ => text::default,
         };
-        let center_text = state
-            .get_file_path()
-            .map(f)
-            .unwrap_or(default_text.to_string());
+        let row = Row::new()
+            .spacing(10)
+            .align_y(Vertical::Center)
+            .push(label(format!("{} - {}", job.path.display(), status)).size(16).style(style).width(Length::Fill));
+        match state.get_view_state() {
+            ViewState::Decrypting => row.push(button(label(state.tr("inspect")).size(14)).on_press(Message::InspectFile(job.path.clone()))).into(),
+            _ => row.into(),
+        }
+    }
+
+    fn summary(state: &State) -> Option<Element<'_, Message>> {
+        let jobs = state.get_jobs();
+        if jobs.is_empty() || jobs.iter().any(|job| matches!(job.status, JobStatus::Pending | JobStatus::Running | JobStatus::Conflict(_))) {
+            return None;
+        }
+        let failed = jobs.iter().filter(|job| matches!(job.status, JobStatus::Failed(_))).count();
+        let warned = jobs.iter().any(|job| matches!(job.status, JobStatus::Done(_, Some(_))));
+        let style = match failed == 0 && !warned {
+            true => theme::success,
+            false => theme::warning,
+        };
+        let finished = state.tr_args("jobs-finished", &[("succeeded", (jobs.len() - failed).into()), ("failed", failed.into())]);
+        Some(label(finished).size(20).style(style).into())
+    }
+
+    pub fn view(state: &State) -> Element<'_, Message> {
+        let (center_text, run_label) = match state.get_view_state() {
+            ViewState::Encrypting => (state.tr("encrypt-title"), state.tr("encrypt-to")),
+            ViewState::Decrypting => (state.tr("decrypt-title"), state.tr("decrypt-to")),
+            _ => panic!()
+        };
+        let idle = !state.is_running_jobs();
+        let toolbar = Row::new()
+            .spacing(10)
+            .push(button(label(shortcut_label(state, Action::AddFiles))).on_press(Message::PickFiles))
+            .push(button(label(shortcut_label_with(state, run_label, Action::Run))).on_press_maybe((idle && state.has_pending_jobs()).then_some(Message::PickOutputDir)))
+            .push(button(label(shortcut_label(state, Action::ClearQueue))).on_press_maybe((idle && !state.get_jobs().is_empty()).then_some(Message::ClearJobs)))
+            .push(button(label(state.tr("inspect-file"))).on_press(Message::PickInspectFile));
+        let jobs = state
+            .get_jobs()
+            .iter()
+            .fold(Column::new().spacing(4), |column, job| column.push(job_row(state, job)));
         let content = Column::new()
-            .spacing(10) 
+            .spacing(10)
             .align_x(Horizontal::Center)
             .push(
-                text(center_text)
-                    .size(48)
-                    .shaping(text::Shaping::Advanced),
-            );
+                label(center_text)
+                    .size(48),
+            )
+            .push(toolbar)
+            .push(Scrollable::new(jobs).height(Length::FillPortion(1)))
+            .push_maybe(summary(state))
+            .push(text_panel(state));
+        let content = match state.get_view_state() {
+            ViewState::Encrypting => {
+                let encoding = pick_list(OutputEncoding::ALL, Some(state.get_output_encoding()), Message::SelectOutputEncoding);
+                let options = Row::new().spacing(10).push(label(state.tr("output-encoding"))).push(encoding);
+                let options = match state.get_contacts().is_empty() {
+                    true => options,
+                    false => options.push(
+                        pick_list(state.get_contacts(), state.get_recipient(), Message::SelectRecipient)
+                            .placeholder(state.tr("recipient-placeholder"))
+                            .text_shaping(text::Shaping::Advanced),
+                    )
+                    .push(button(label(state.tr("recipient-clear"))).on_press_maybe(state.get_recipient().map(|_| Message::ClearRecipient))),
+                };
+                let shred = checkbox(state.tr("shred-originals"), state.is_shredding_originals())
+                    .on_toggle_maybe(state.get_verification_key().map(|_| Message::SetShredOriginals))
+                    .text_shaping(text::Shaping::Advanced);
+                let shred_hint = state
+                    .get_verification_key()
+                    .is_none()
+                    .then(|| label(state.tr("shred-needs-private-key")).style(theme::warning).size(14));
+                content
+                    .push(options.align_y(Vertical::Center))
+                    .push(Row::new().spacing(10).align_y(Vertical::Center).push(shred).push_maybe(shred_hint))
+            }
+            _ => content,
+        };
         let container = container(content)
             .center_x(Length::Fill)
             .center_y(Length::Fill)
@@ -98,4 +286,492 @@ pub mod calculate {
     
         container.into()
     }
-}
\ No newline at end of file
+}
+
+pub mod preferences {
+    use std::fmt::{self, Display};
+    use iced::{alignment::{Horizontal, Vertical}, widget::{button, center, container, opaque, pick_list, text, Column, Row}, Element, Length};
+    use crate::{core::{AeadAlgorithm, CompressionMode, KeyTransport, Language, OutputEncoding, Settings, ThemeMode, EXPONENTS, KEY_SIZES}, gui::{message::Message, state::State, theme}};
+    use super::label;
+
+    #[derive(Clone, PartialEq)]
+    struct Choice<T> {
+        value: T,
+        label: String,
+    }
+
+    impl<T> Display for Choice<T> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "{}", self.label)
+        }
+    }
+
+    fn choices<T: Copy>(state: &State, values: &[T], label_id: fn(T) -> &'static str) -> Vec<Choice<T>> {
+        values
+            .iter()
+            .map(|value| Choice { value: *value, label: state.tr(label_id(*value)) })
+            .collect()
+    }
+
+    fn theme_label_id(theme: ThemeMode) -> &'static str {
+        match theme {
+            ThemeMode::System => "theme-system",
+            ThemeMode::Light => "theme-light",
+            ThemeMode::Dark => "theme-dark",
+            ThemeMode::HighContrast => "theme-high-contrast",
+        }
+    }
+
+    fn language_label_id(language: Language) -> &'static str {
+        match language {
+            Language::System => "language-system",
+            Language::English => "language-english",
+            Language::Chinese => "language-chinese",
+        }
+    }
+
+    fn compression_label_id(compression: CompressionMode) -> &'static str {
+        match compression {
+            CompressionMode::Off => "compression-off",
+            CompressionMode::Auto => "compression-auto",
+            CompressionMode::Zstd => "compression-zstd",
+            CompressionMode::Deflate => "compression-deflate",
+        }
+    }
+
+    fn option<'a>(state: &State, label_id: &str, input: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+        Row::new()
+            .spacing(10)
+            .align_y(Vertical::Center)
+            .push(label(state.tr(label_id)).width(200))
+            .push(input)
+            .into()
+    }
+
+    fn edit<T>(settings: &Settings, f: fn(&mut Settings, T)) -> impl Fn(T) -> Message {
+        let settings = settings.clone();
+        move |value| {
+            let mut settings = settings.clone();
+            f(&mut settings, value);
+            Message::SaveSettings(settings)
+        }
+    }
+
+    pub fn view(state: &State) -> Element<'_, Message> {
+        let settings = state.get_settings();
+        let output_dir = settings
+            .output_dir
+            .as_ref()
+            .map(|output_dir| output_dir.display().to_string())
+            .unwrap_or(state.tr("pref-output-dir-ask"));
+        let clear_output_dir = settings
+            .output_dir
+            .as_ref()
+            .map(|_| Message::SaveSettings(Settings { output_dir: None, ..settings.clone() }));
+        let output_dir = Row::new()
+            .spacing(10)
+            .align_y(Vertical::Center)
+            .push(label(output_dir))
+            .push(button(label(state.tr("choose"))).on_press(Message::PickDefaultOutputDir))
+            .push(button(label(state.tr("clear"))).on_press_maybe(clear_output_dir));
+        let themes = choices(state, &ThemeMode::ALL, theme_label_id);
+        let theme = themes.iter().find(|choice| choice.value == settings.theme).cloned();
+        let set_theme = edit(settings, |settings, value| settings.theme = value);
+        let theme = pick_list(themes, theme, move |choice: Choice<ThemeMode>| set_theme(choice.value))
+            .text_shaping(text::Shaping::Advanced);
+        let languages = choices(state, &Language::ALL, language_label_id);
+        let language = languages.iter().find(|choice| choice.value == settings.language).cloned();
+        let set_language = edit(settings, |settings, value| settings.language = value);
+        let language = pick_list(languages, language, move |choice: Choice<Language>| set_language(choice.value))
+            .text_shaping(text::Shaping::Advanced);
+        let aead = Row::new()
+            .spacing(10)
+            .align_y(Vertical::Center)
+            .push(pick_list(AeadAlgorithm::ALL, Some(settings.aead), edit(settings, |settings, value| settings.aead = value)))
+            .push(button(label(state.tr("benchmark"))).on_press(Message::ToggleBenchmark));
+        let compressions = choices(state, &CompressionMode::ALL, compression_label_id);
+        let compression = compressions.iter().find(|choice| choice.value == settings.compression).cloned();
+        let set_compression = edit(settings, |settings, value| settings.compression = value);
+        let compression = pick_list(compressions, compression, move |choice: Choice<CompressionMode>| set_compression(choice.value))
+            .text_shaping(text::Shaping::Advanced);
+        let content = Column::new()
+            .spacing(16)
+            .align_x(Horizontal::Left)
+            .push(label(state.tr("preferences-title")).size(48))
+            .push(option(state, "pref-key-size", pick_list(KEY_SIZES, Some(settings.key_size), edit(settings, |settings, value| settings.key_size = value))))
+            .push(option(state, "pref-exponent", pick_list(EXPONENTS, Some(settings.exponent), edit(settings, |settings, value| settings.exponent = value))))
+            .push(option(state, "pref-key-transport", pick_list(KeyTransport::ALL, Some(settings.key_transport), edit(settings, |settings, value| settings.key_transport = value))))
+            .push(option(state, "pref-aead", aead))
+            .push(option(state, "pref-compression", compression))
+            .push(option(state, "pref-output-encoding", pick_list(OutputEncoding::ALL, Some(settings.output_encoding), edit(settings, |settings, value| settings.output_encoding = value))))
+            .push(option(state, "pref-output-dir", output_dir))
+            .push(option(state, "pref-theme", theme))
+            .push(option(state, "pref-language", language));
+        container(content)
+            .center_x(Length::Fill)
+            .center_y(Length::Fill)
+            .padding(20)
+            .into()
+    }
+
+    pub fn benchmark(state: &State) -> Option<Element<'_, Message>> {
+        if !state.is_showing_benchmark() {
+            return None;
+        }
+        let results = match state.get_benchmark() {
+            None => Column::new().push(label(state.tr("benchmark-running"))),
+            Some(Err(err)) => Column::new().push(label(state.tr_args("benchmark-failed", &[("error", err.as_str().into())])).style(theme::error)),
+            Some(Ok(results)) => results.iter().fold(Column::new().spacing(8), |column, (aead, throughput)| {
+                let throughput = state.tr_args("benchmark-throughput", &[("speed", format!("{:.0}", throughput / (1024. * 1024.)).into())]);
+                let style = match *aead == state.get_settings().aead {
+                    true => theme::success,
+                    false => text::default,
+                };
+                column.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(label(aead.to_string()).width(220).style(style))
+                        .push(label(throughput).style(style)),
+                )
+            }),
+        };
+        let buttons = Row::new()
+            .spacing(10)
+            .push(button(label(state.tr("benchmark-run-again"))).on_press_maybe(state.get_benchmark().map(|_| Message::RunBenchmark)))
+            .push(button(label(state.tr("close"))).on_press(Message::ToggleBenchmark));
+        let content = Column::new()
+            .spacing(15)
+            .padding(20)
+            .width(Length::Fill)
+            .max_width(600)
+            .align_x(Horizontal::Center)
+            .push(label(state.tr("benchmark-title")).size(24))
+            .push(label(state.tr("benchmark-hint")).size(14))
+            .push(results)
+            .push(buttons);
+        Some(opaque(center(container(content).style(container::rounded_box)).padding(20)))
+    }
+}
+
+pub mod navigation {
+    use iced::{alignment::Horizontal, widget::{button, center, container, opaque, Column, Row}, Element};
+    use crate::gui::{keymap::Action, message::Message, state::{State, ViewState}};
+    use super::label;
+
+    pub fn shortcut_label_with(state: &State, label: String, action: Action) -> String {
+        match state.get_keymap().binding(state.get_view_state(), action) {
+            Some(binding) => format!("{} ({})", label, binding),
+            None => label,
+        }
+    }
+
+    pub fn shortcut_label(state: &State, action: Action) -> String {
+        shortcut_label_with(state, state.tr(action.label_id()), action)
+    }
+
+    pub fn tab_bar(state: &State) -> Element<'_, Message> {
+        let tabs = [
+            (ViewState::Setting, "tab-setting"),
+            (ViewState::Encrypting, "tab-encrypt"),
+            (ViewState::Decrypting, "tab-decrypt"),
+            (ViewState::Preferences, "tab-preferences"),
+        ];
+        let enabled = !state.is_running_jobs();
+        tabs.into_iter()
+            .fold(Row::new().spacing(10).padding(10), |row, (view_state, label_id)| {
+                let selectable = enabled && view_state != state.get_view_state() && state.can_select_view_state(view_state);
+                row.push(button(label(state.tr(label_id))).on_press_maybe(selectable.then_some(Message::SelectView(view_state))))
+            })
+            .push(button(label(shortcut_label_with(state, state.tr("shortcuts"), Action::CheatSheet))).on_press(Message::ToggleCheatSheet))
+            .push(button(label(state.tr("history"))).on_press(Message::ToggleHistory))
+            .into()
+    }
+
+    pub fn cheat_sheet(state: &State) -> Element<'_, Message> {
+        let keymap = state.get_keymap();
+        let bindings = keymap
+            .bindings(state.get_view_state())
+            .fold(Column::new().spacing(6), |column, (action, binding)| {
+                column.push(label(format!("{} -> {}", binding, state.tr(action.label_id()))).size(18))
+            });
+        let problems = keymap
+            .get_problems()
+            .iter()
+            .fold(Column::new().spacing(4), |column, problem| column.push(label(problem.describe(state)).size(14)));
+        let content = Column::new()
+            .spacing(10)
+            .padding(20)
+            .align_x(Horizontal::Center)
+            .push(label(state.tr("shortcuts-title")).size(24))
+            .push(bindings)
+            .push(label(state.tr("shortcuts-hint")).size(14))
+            .push(problems)
+            .push(button(label(state.tr("close"))).on_press(Message::ToggleCheatSheet));
+        opaque(center(container(content).style(container::rounded_box)))
+    }
+}
+
+pub mod notification {
+    use iced::{alignment::{Horizontal, Vertical}, widget::{button, checkbox, container, opaque, center, Column, Row, Scrollable}, Element, Length};
+    use crate::gui::{message::Message, state::{ConflictChoice, HistoryEntry, Outcome, State, ToastKind}, theme};
+    use super::label;
+
+    pub fn toasts(state: &State) -> Element<'_, Message> {
+        let toasts = state
+            .get_toasts()
+            .iter()
+            .fold(Column::new().spacing(10).width(400), |column, toast| {
+                let style = match toast.kind {
+                    ToastKind::Success => theme::success,
+                    ToastKind::Warning => theme::warning,
+                    ToastKind::Error => theme::error,
+                };
+                let row = Row::new()
+                    .spacing(10)
+                    .align_y(Vertical::Center)
+                    .push(label(&toast.text).style(style).width(Length::Fill))
+                    .push(button(label("×")).on_press(Message::DismissToast(toast.id)));
+                column.push(container(row).padding(10).style(container::rounded_box))
+            });
+        container(toasts)
+            .align_x(Horizontal::Right)
+            .align_y(Vertical::Bottom)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(20)
+            .into()
+    }
+
+    fn history_row<'a>(state: &State, entry: &'a HistoryEntry) -> Element<'a, Message> {
+        let report = &entry.report;
+        let outcome = match &report.outcome {
+            Outcome::Succeeded(_) => state.tr("history-succeeded"),
+            Outcome::Replaced(_) => state.tr("history-replaced"),
+            Outcome::Cancelled => state.tr("history-cancelled"),
+            Outcome::Failed(err) => state.tr_args("job-failed", &[("error", err.as_str().into())]),
+        };
+        let style = match &report.outcome {
+            Outcome::Succeeded(_) | Outcome::Replaced(_) => theme::success,
+            Outcome::Cancelled => theme::warning,
+            Outcome::Failed(_) => theme::error,
+        };
+        let input = report.input.as_ref().map(|path| path.display().to_string()).unwrap_or("-".to_string());
+        let fingerprint = report.fingerprint.as_ref().map(|fingerprint| fingerprint.chars().take(16).collect()).unwrap_or("-".to_string());
+        let output = match &report.outcome {
+            Outcome::Succeeded(Some(output_path)) | Outcome::Replaced(output_path) => output_path.display().to_string(),
+            _ => "-".to_string(),
+        };
+        Column::new()
+            .spacing(2)
+            .push(label(format!("{}  {}", entry.timestamp.format("%Y-%m-%d %H:%M:%S"), state.tr(report.operation.label_id()))).size(16))
+            .push(label(state.tr_args("history-details", &[("input", input.into()), ("fingerprint", fingerprint.into()), ("output", output.into())])).size(13))
+            .push(label(outcome).style(style).size(13))
+            .into()
+    }
+
+    pub fn history(state: &State) -> Element<'_, Message> {
+        let entries = state
+            .get_history()
+            .iter()
+            .rev()
+            .fold(Column::new().spacing(12), |column, entry| column.push(history_row(state, entry)));
+        let entries = match state.get_history().is_empty() {
+            true => entries.push(label(state.tr("history-empty"))),
+            false => entries,
+        };
+        let buttons = Row::new()
+            .spacing(10)
+            .push(button(label(state.tr("clear"))).on_press_maybe((!state.get_history().is_empty()).then_some(Message::ClearHistory)))
+            .push(button(label(state.tr("close"))).on_press(Message::ToggleHistory));
+        let content = Column::new()
+            .spacing(10)
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Horizontal::Center)
+            .push(label(state.tr("history-title")).size(24))
+            .push(Scrollable::new(entries).height(Length::Fill).width(Length::Fill))
+            .push(buttons);
+        opaque(center(container(content).max_width(800).max_height(600).style(container::rounded_box)).padding(20))
+    }
+
+    pub fn conflict(state: &State) -> Option<Element<'_, Message>> {
+        let conflict = state.get_conflict()?;
+        let buttons = Row::new()
+            .spacing(10)
+            .push(button(label(state.tr("conflict-replace"))).on_press(Message::ResolveConflict(ConflictChoice::Replace)))
+            .push(button(label(state.tr("conflict-keep-both"))).on_press(Message::ResolveConflict(ConflictChoice::KeepBoth)))
+            .push(button(label(state.tr("conflict-skip"))).on_press(Message::ResolveConflict(ConflictChoice::Skip)));
+        let apply_to_all = checkbox(state.tr("conflict-apply-to-all"), state.is_applying_to_all_conflicts())
+            .on_toggle(Message::SetApplyToAllConflicts);
+        let content = Column::new()
+            .spacing(15)
+            .padding(20)
+            .width(Length::Fill)
+            .max_width(600)
+            .align_x(Horizontal::Center)
+            .push(label(state.tr("conflict-title")).size(24))
+            .push(label(state.tr_args("conflict-exists", &[("path", conflict.path.display().to_string().into())])))
+            .push(apply_to_all)
+            .push(buttons);
+        Some(opaque(center(container(content).style(container::rounded_box)).padding(20)))
+    }
+
+    pub fn shred_confirmation(state: &State) -> Option<Element<'_, Message>> {
+        if !state.is_confirming_shred() {
+            return None;
+        }
+        let buttons = Row::new()
+            .spacing(10)
+            .push(button(label(state.tr("shred-enable"))).style(button::danger).on_press(Message::ConfirmShred(true)))
+            .push(button(label(state.tr("cancel"))).on_press(Message::ConfirmShred(false)));
+        let content = Column::new()
+            .spacing(15)
+            .padding(20)
+            .width(Length::Fill)
+            .max_width(600)
+            .align_x(Horizontal::Center)
+            .push(label(state.tr("shred-title")).size(24))
+            .push(label(state.tr("shred-explanation")))
+            .push(label(state.tr("shred-warning")).style(theme::warning))
+            .push(buttons);
+        Some(opaque(center(container(content).style(container::rounded_box)).padding(20)))
+    }
+}
+
+pub mod inspector {
+    use chrono::{DateTime, Local};
+    use iced::{alignment::{Horizontal, Vertical}, widget::{button, checkbox, container, opaque, center, text, Column, Row, Scrollable}, Element, Length};
+    use crate::{core::{Compression, Inspection, PayloadKind}, gui::{message::Message, state::{KeyOwner, State}, theme}};
+    use super::label;
+
+    fn field<'a>(name: String, value: String) -> Element<'a, Message> {
+        Row::new()
+            .spacing(10)
+            .push(label(name).width(160))
+            .push(label(value))
+            .into()
+    }
+
+    fn payload_label_id(payload_kind: PayloadKind) -> &'static str {
+        match payload_kind {
+            PayloadKind::File => "inspect-payload-file",
+            PayloadKind::Archive => "inspect-payload-archive",
+            PayloadKind::Text => "inspect-payload-text",
+        }
+    }
+
+    fn details<'a>(state: &State, inspection: &'a Inspection) -> Column<'a, Message> {
+        let created = DateTime::from_timestamp(inspection.created as i64, 0)
+            .map(|created| created.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or("-".to_string());
+        let compression = match inspection.compression {
+            Compression::None => state.tr("compression-none"),
+            compression => compression.to_string(),
+        };
+        let chunks = state.tr_args("inspect-chunks-value", &[("count", inspection.chunk_count.into()), ("size", inspection.chunk_size.into())]);
+        let payload_size = state.tr_args("inspect-payload-size-value", &[("size", inspection.payload_size.into())]);
+        let recipients = inspection
+            .recipients
+            .iter()
+            .fold(Column::new().spacing(6), |column, (fingerprint, key_transport)| {
+                let owner = match state.find_key_owner(fingerprint) {
+                    Some(KeyOwner::PrivateKey) => label(state.tr("inspect-owner-private-key")).style(theme::success),
+                    Some(KeyOwner::PublicKey) => label(state.tr("inspect-owner-public-key")),
+                    Some(KeyOwner::Contact(name)) => label(state.tr_args("inspect-owner-contact", &[("name", name.into())])),
+                    None => label(state.tr("inspect-owner-unknown")).style(theme::warning),
+                };
+                column.push(
+                    Column::new()
+                        .push(label(fingerprint.clone()).size(13))
+                        .push(Row::new().spacing(10).push(label(key_transport.to_string()).size(13)).push(owner.size(13))),
+                )
+            });
+        Column::new()
+            .spacing(8)
+            .push(field(state.tr("inspect-version"), inspection.version.to_string()))
+            .push(field(state.tr("inspect-created"), created))
+            .push(field(state.tr("inspect-payload"), state.tr(payload_label_id(inspection.payload_kind))))
+            .push(field(state.tr("inspect-aead"), inspection.aead.to_string()))
+            .push(field(state.tr("inspect-compression"), compression))
+            .push(field(state.tr("inspect-chunks"), chunks))
+            .push(field(state.tr("inspect-payload-size"), payload_size))
+            .push(label(state.tr_args("inspect-recipients", &[("count", inspection.recipients.len().into())])).size(18))
+            .push(recipients)
+    }
+
+    fn owner_name(state: &State, fingerprint: &str) -> String {
+        match state.find_key_owner(fingerprint) {
+            Some(KeyOwner::PrivateKey) => state.tr("inspect-owner-private-key"),
+            Some(KeyOwner::PublicKey) => state.tr("inspect-owner-public-key"),
+            Some(KeyOwner::Contact(name)) => state.tr_args("inspect-owner-contact", &[("name", name.into())]),
+            None => state.tr("inspect-owner-unknown"),
+        }
+    }
+
+    fn recipient_key<'a>(state: &State, selection: &[String], fingerprint: &str) -> Element<'a, Message> {
+        let fingerprint = fingerprint.to_string();
+        let name = format!("{}  {}", owner_name(state, &fingerprint), &fingerprint[..16]);
+        checkbox(name, selection.contains(&fingerprint))
+            .on_toggle_maybe((!state.is_saving_recipients()).then_some(move |selected| Message::SelectRecipientKey(fingerprint.clone(), selected)))
+            .text_shaping(text::Shaping::Advanced)
+            .into()
+    }
+
+    fn recipients_editor<'a>(state: &State, inspection: &'a Inspection, selection: &[String]) -> Column<'a, Message> {
+        let current = inspection
+            .recipients
+            .iter()
+            .fold(Column::new().spacing(6), |column, (fingerprint, _)| column.push(recipient_key(state, selection, fingerprint)));
+        let candidates = state
+            .get_recipient_candidates()
+            .into_iter()
+            .filter(|(candidate, _)| inspection.recipients.iter().all(|(fingerprint, _)| fingerprint != candidate))
+            .fold(Column::new().spacing(6), |column, (fingerprint, _)| column.push(recipient_key(state, selection, &fingerprint)));
+        let transport = state.tr_args("recipients-transport", &[("transport", state.get_settings().key_transport.to_string().into())]);
+        let empty = selection.is_empty().then(|| label(state.tr("recipients-empty")).style(theme::warning));
+        Column::new()
+            .spacing(8)
+            .push(label(state.tr("recipients-current")).size(18))
+            .push(current)
+            .push(label(state.tr("recipients-add")).size(18))
+            .push(candidates)
+            .push(label(transport).size(13))
+            .push(label(state.tr("recipients-removed-hint")).size(13))
+            .push_maybe(empty)
+    }
+
+    fn actions<'a>(state: &State, result: &Result<Inspection, String>) -> Row<'a, Message> {
+        let row = Row::new().spacing(10).align_y(Vertical::Center);
+        match (result, state.get_recipients_editor()) {
+            (Ok(_), Some(selection)) => row
+                .push(button(label(state.tr("recipients-save"))).on_press_maybe((!selection.is_empty() && !state.is_saving_recipients()).then_some(Message::SaveRecipients)))
+                .push(button(label(state.tr("cancel"))).on_press_maybe((!state.is_saving_recipients()).then_some(Message::CloseRecipientsEditor))),
+            (Ok(_), None) => row
+                .push(button(label(state.tr("recipients-edit"))).on_press_maybe(state.get_rewrap_key().map(|_| Message::EditRecipients)))
+                .push(button(label(state.tr("close"))).on_press(Message::CloseInspection))
+                .push_maybe(state.get_rewrap_key().is_none().then(|| label(state.tr("recipients-need-key")).size(13))),
+            (Err(_), _) => row.push(button(label(state.tr("close"))).on_press(Message::CloseInspection)),
+        }
+    }
+
+    pub fn view(state: &State) -> Option<Element<'_, Message>> {
+        let (path, result) = state.get_inspection()?;
+        let body = match (result, state.get_recipients_editor()) {
+            (Ok(inspection), Some(selection)) => recipients_editor(state, inspection, selection),
+            (Ok(inspection), None) => details(state, inspection),
+            (Err(err), _) => Column::new().push(label(state.tr_args("inspect-failed", &[("error", err.as_str().into())])).style(theme::error)),
+        };
+        let content = Column::new()
+            .spacing(10)
+            .padding(20)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Horizontal::Center)
+            .push(label(state.tr("inspect-title")).size(24))
+            .push(label(path.display().to_string()).size(13))
+            .push(Scrollable::new(body).height(Length::Fill).width(Length::Fill))
+            .push(actions(state, result));
+        Some(opaque(center(container(content).max_width(800).max_height(600).style(container::rounded_box)).padding(20)))
+    }
+}
@@ -0,0 +1,134 @@
+use std::sync::LazyLock;
+
+use anyhow::Error;
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+use crate::core::{KeyDiagnostic, Language, Problem};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+    #[default]
+    English,
+    Chinese,
+}
+
+const CATALOGS: [(Locale, &str, &str); 2] = [
+    (Locale::English, "en-US", include_str!("locales/en-US.ftl")),
+    (Locale::Chinese, "zh-CN", include_str!("locales/zh-CN.ftl")),
+];
+
+static BUNDLES: LazyLock<Vec<(Locale, FluentBundle<FluentResource>)>> = LazyLock::new(|| {
+    CATALOGS
+        .iter()
+        .map(|(locale, id, source)| {
+            let language: LanguageIdentifier = id.parse().expect("Invalid catalog language!");
+            let resource = FluentResource::try_new(source.to_string()).expect("Invalid catalog!");
+            let mut bundle = FluentBundle::new_concurrent(vec![language]);
+            bundle.set_use_isolating(false);
+            bundle.add_resource(resource).expect("Duplicate catalog message!");
+            (*locale, bundle)
+        })
+        .collect()
+});
+
+impl Locale {
+    pub fn resolve(language: Language) -> Self {
+        match language {
+            Language::English => Locale::English,
+            Language::Chinese => Locale::Chinese,
+            Language::System => match sys_locale::get_locale() {
+                Some(locale) if locale.starts_with("zh") => Locale::Chinese,
+                _ => Locale::English,
+            },
+        }
+    }
+}
+
+fn format(locale: Locale, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let (_, bundle) = BUNDLES.iter().find(|(bundle_locale, _)| *bundle_locale == locale)?;
+    let pattern = bundle.get_message(id)?.value()?;
+    let mut errors = Vec::new();
+    Some(bundle.format_pattern(pattern, args, &mut errors).into_owned())
+}
+
+// Missing messages fall back to English and then to the message id, so a
+// gap in a catalog shows up on screen instead of as an empty label.
+pub fn tr_args(locale: Locale, id: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+    let args = match args.is_empty() {
+        true => None,
+        false => Some(args.iter().cloned().collect::<FluentArgs>()),
+    };
+    format(locale, id, args.as_ref())
+        .or_else(|| format(Locale::English, id, args.as_ref()))
+        .unwrap_or(id.to_string())
+}
+
+pub fn tr(locale: Locale, id: &str) -> String {
+    tr_args(locale, id, &[])
+}
+
+fn tr_problem(locale: Locale, problem: &Problem) -> String {
+    let args: Vec<(&str, FluentValue<'_>)> = problem.args.iter().map(|(name, value)| (*name, value.as_str().into())).collect();
+    tr_args(locale, problem.id, &args)
+}
+
+// Errors from outside the core, such as I/O or parser failures, only have
+// their English text.
+pub fn tr_error(locale: Locale, err: &Error) -> String {
+    if let Some(problem) = err.downcast_ref::<Problem>() {
+        return tr_problem(locale, problem);
+    }
+    match err.downcast_ref::<KeyDiagnostic>() {
+        Some(KeyDiagnostic { problem, line: Some(line) }) => {
+            tr_args(locale, "key-line", &[("line", (*line).into()), ("problem", tr_problem(locale, problem).into())])
+        }
+        Some(KeyDiagnostic { problem, line: None }) => tr_problem(locale, problem),
+        None => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use fluent_bundle::FluentResource;
+    use anyhow::Error;
+    use fluent_syntax::ast::Entry;
+
+    use crate::core::Problem;
+
+    use super::{tr_error, Locale, CATALOGS};
+
+    fn message_ids(source: &str) -> BTreeSet<String> {
+        let resource = FluentResource::try_new(source.to_string()).expect("Catalog has syntax errors!");
+        resource
+            .entries()
+            .filter_map(|entry| match entry {
+                Entry::Message(message) => Some(message.id.name.to_string()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn catalogs_have_the_same_keys() {
+        let (_, reference_id, reference) = CATALOGS[0];
+        let expected = message_ids(reference);
+        for (_, id, source) in CATALOGS.iter().skip(1) {
+            let actual = message_ids(source);
+            let missing: Vec<_> = expected.difference(&actual).collect();
+            let extra: Vec<_> = actual.difference(&expected).collect();
+            assert!(missing.is_empty(), "{} is missing keys from {}: {:?}", id, reference_id, missing);
+            assert!(extra.is_empty(), "{} has keys that {} lacks: {:?}", id, reference_id, extra);
+        }
+    }
+
+    #[test]
+    fn problems_are_translated_with_their_arguments() {
+        let err = Error::new(Problem::new("key-unsupported-pem-label").arg("label", "DSA PRIVATE KEY"));
+        assert_eq!(tr_error(Locale::English, &err), "Unsupported PEM label \"DSA PRIVATE KEY\"!");
+        assert_eq!(tr_error(Locale::Chinese, &err), "不支持的 PEM 标签“DSA PRIVATE KEY”！");
+        assert_eq!(tr_error(Locale::Chinese, &Error::msg("disk full")), "disk full");
+    }
+}
@@ -0,0 +1,423 @@
+use std::{collections::BTreeMap, fmt::{self, Display}};
+
+use anyhow::Result;
+use iced::keyboard::{key::Named, Key, Modifiers};
+use serde::Deserialize;
+use tokio::fs;
+
+use crate::core::config_dir;
+
+use super::{message::Message, state::{State, ViewState}};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    GenerateKeys,
+    SaveKeys,
+    SwitchView,
+    AddFiles,
+    Run,
+    ClearQueue,
+    DecryptFromClipboard,
+    CheatSheet,
+}
+
+impl Action {
+    const ALL: [Action; 8] = [
+        Action::GenerateKeys,
+        Action::SaveKeys,
+        Action::SwitchView,
+        Action::AddFiles,
+        Action::Run,
+        Action::ClearQueue,
+        Action::DecryptFromClipboard,
+        Action::CheatSheet,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Action::GenerateKeys => "generate_keys",
+            Action::SaveKeys => "save_keys",
+            Action::SwitchView => "switch_view",
+            Action::AddFiles => "add_files",
+            Action::Run => "run",
+            Action::ClearQueue => "clear_queue",
+            Action::DecryptFromClipboard => "decrypt_from_clipboard",
+            Action::CheatSheet => "cheat_sheet",
+        }
+    }
+
+    pub fn label_id(self) -> &'static str {
+        match self {
+            Action::GenerateKeys => "action-generate-keys",
+            Action::SaveKeys => "action-save-keys",
+            Action::SwitchView => "action-switch-view",
+            Action::AddFiles => "action-add-files",
+            Action::Run => "action-run",
+            Action::ClearQueue => "action-clear-queue",
+            Action::DecryptFromClipboard => "action-decrypt-from-clipboard",
+            Action::CheatSheet => "action-cheat-sheet",
+        }
+    }
+
+    fn is_available_in(self, view_state: ViewState) -> bool {
+        match self {
+            Action::GenerateKeys | Action::SaveKeys => view_state == ViewState::Setting,
+            Action::AddFiles | Action::Run | Action::ClearQueue => matches!(view_state, ViewState::Encrypting | ViewState::Decrypting),
+            Action::DecryptFromClipboard => view_state == ViewState::Decrypting,
+            Action::SwitchView | Action::CheatSheet => true,
+        }
+    }
+
+    pub fn message(self) -> Message {
+        match self {
+            Action::GenerateKeys => Message::GenerateKeys,
+            Action::SaveKeys => Message::SaveKeys,
+            Action::SwitchView => Message::SwitchView,
+            Action::AddFiles => Message::PickFiles,
+            Action::Run => Message::PickOutputDir,
+            Action::ClearQueue => Message::ClearJobs,
+            Action::DecryptFromClipboard => Message::DecryptFromClipboard,
+            Action::CheatSheet => Message::ToggleCheatSheet,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingError {
+    NoKey(String),
+    UnknownModifier(String, String),
+    UnknownKey(String, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeymapProblem {
+    Unavailable(ViewState, Action),
+    InvalidBinding(ViewState, BindingError),
+    Conflict(ViewState, Binding, Action),
+    Syntax(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeyName {
+    Character(String),
+    Named(Named),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    ctrl: bool,
+    alt: bool,
+    shift: bool,
+    key: KeyName,
+}
+
+const NAMED_KEYS: [(&str, Named); 23] = [
+    ("Tab", Named::Tab),
+    ("Enter", Named::Enter),
+    ("Delete", Named::Delete),
+    ("Backspace", Named::Backspace),
+    ("Escape", Named::Escape),
+    ("Space", Named::Space),
+    ("Insert", Named::Insert),
+    ("Home", Named::Home),
+    ("End", Named::End),
+    ("PageUp", Named::PageUp),
+    ("PageDown", Named::PageDown),
+    ("F1", Named::F1),
+    ("F2", Named::F2),
+    ("F3", Named::F3),
+    ("F4", Named::F4),
+    ("F5", Named::F5),
+    ("F6", Named::F6),
+    ("F7", Named::F7),
+    ("F8", Named::F8),
+    ("F9", Named::F9),
+    ("F10", Named::F10),
+    ("F11", Named::F11),
+    ("F12", Named::F12),
+];
+
+impl Binding {
+    pub fn parse(text: &str) -> Result<Self, BindingError> {
+        let mut binding = Binding { ctrl: false, alt: false, shift: false, key: KeyName::Character(String::new()) };
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key = parts.pop().filter(|key| !key.is_empty()).ok_or(BindingError::NoKey(text.to_string()))?;
+        for modifier in parts {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" | "command" => binding.ctrl = true,
+                "alt" | "option" => binding.alt = true,
+                "shift" => binding.shift = true,
+                _ => return Err(BindingError::UnknownModifier(modifier.to_string(), text.to_string())),
+            }
+        }
+        binding.key = match NAMED_KEYS.iter().find(|(name, _)| name.eq_ignore_ascii_case(key)) {
+            Some((_, named)) => KeyName::Named(*named),
+            None if key.chars().count() == 1 => KeyName::Character(key.to_lowercase()),
+            None => return Err(BindingError::UnknownKey(key.to_string(), text.to_string())),
+        };
+        Ok(binding)
+    }
+
+    fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        let key_matches = match (&self.key, key) {
+            (KeyName::Character(expected), Key::Character(character)) => *expected == character.to_lowercase(),
+            (KeyName::Named(expected), Key::Named(named)) => expected == named,
+            _ => false,
+        };
+        key_matches
+            && self.ctrl == (modifiers.control() || modifiers.command())
+            && self.alt == modifiers.alt()
+            && self.shift == modifiers.shift()
+    }
+}
+
+impl Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        match &self.key {
+            KeyName::Character(character) => write!(f, "{}", character.to_uppercase()),
+            KeyName::Named(named) => {
+                let name = NAMED_KEYS.iter().find(|(_, key)| key == named).map(|(name, _)| *name).unwrap_or("?");
+                write!(f, "{}", name)
+            }
+        }
+    }
+}
+
+// Bindings as written in keymap.toml, one table per view:
+//
+// [encrypt]
+// run = "Ctrl+E, Enter"
+// clear_queue = ""     # unbind
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct KeymapFile {
+    #[serde(default)]
+    setting: BTreeMap<Action, String>,
+    #[serde(default)]
+    encrypt: BTreeMap<Action, String>,
+    #[serde(default)]
+    decrypt: BTreeMap<Action, String>,
+    #[serde(default)]
+    preferences: BTreeMap<Action, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(ViewState, Action, Binding)>,
+    problems: Vec<KeymapProblem>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::from_file(KeymapFile::default())
+    }
+}
+
+fn view_name(view_state: ViewState) -> &'static str {
+    match view_state {
+        ViewState::Setting => "setting",
+        ViewState::Encrypting => "encrypt",
+        ViewState::Decrypting => "decrypt",
+        ViewState::Preferences => "preferences",
+    }
+}
+
+impl KeymapProblem {
+    pub fn describe(&self, state: &State) -> String {
+        match self {
+            Self::Unavailable(view_state, action) => {
+                state.tr_args("keymap-unavailable", &[("view", view_name(*view_state).into()), ("action", action.name().into())])
+            }
+            Self::InvalidBinding(view_state, BindingError::NoKey(binding)) => {
+                state.tr_args("keymap-no-key", &[("view", view_name(*view_state).into()), ("binding", binding.as_str().into())])
+            }
+            Self::InvalidBinding(view_state, BindingError::UnknownModifier(modifier, binding)) => state.tr_args(
+                "keymap-unknown-modifier",
+                &[("view", view_name(*view_state).into()), ("modifier", modifier.as_str().into()), ("binding", binding.as_str().into())],
+            ),
+            Self::InvalidBinding(view_state, BindingError::UnknownKey(key, binding)) => state.tr_args(
+                "keymap-unknown-key",
+                &[("view", view_name(*view_state).into()), ("key", key.as_str().into()), ("binding", binding.as_str().into())],
+            ),
+            Self::Conflict(view_state, binding, action) => state.tr_args(
+                "keymap-conflict",
+                &[("view", view_name(*view_state).into()), ("binding", binding.to_string().into()), ("action", action.name().into())],
+            ),
+            Self::Syntax(error) => state.tr_args("keymap-syntax", &[("error", error.as_str().into())]),
+        }
+    }
+}
+
+fn default_binding(view_state: ViewState, action: Action) -> Option<&'static str> {
+    match (view_state, action) {
+        (ViewState::Setting, Action::GenerateKeys) => Some("Ctrl+G"),
+        (ViewState::Setting, Action::SaveKeys) => Some("Ctrl+S"),
+        (_, Action::SwitchView) => Some("Tab"),
+        (_, Action::CheatSheet) => Some("F1"),
+        (ViewState::Setting, _) => None,
+        (_, Action::AddFiles) => Some("Ctrl+O"),
+        (_, Action::Run) => Some("Ctrl+S, Enter"),
+        (_, Action::ClearQueue) => Some("Delete"),
+        (ViewState::Decrypting, Action::DecryptFromClipboard) => Some("Ctrl+V"),
+        _ => None,
+    }
+}
+
+impl Keymap {
+    fn from_file(file: KeymapFile) -> Self {
+        let mut bindings = Vec::new();
+        let mut problems = Vec::new();
+        let views = [
+            (ViewState::Setting, file.setting),
+            (ViewState::Encrypting, file.encrypt),
+            (ViewState::Decrypting, file.decrypt),
+            (ViewState::Preferences, file.preferences),
+        ];
+        for (view_state, overrides) in views {
+            for (action, _) in overrides.iter().filter(|(action, _)| !action.is_available_in(view_state)) {
+                problems.push(KeymapProblem::Unavailable(view_state, *action));
+            }
+            for action in Action::ALL.into_iter().filter(|action| action.is_available_in(view_state)) {
+                let texts = match overrides.get(&action) {
+                    Some(text) => text.as_str(),
+                    None => match default_binding(view_state, action) {
+                        Some(text) => text,
+                        None => continue,
+                    },
+                };
+                for text in texts.split(',').filter(|text| !text.trim().is_empty()) {
+                    match Binding::parse(text) {
+                        Ok(binding) => bindings.push((view_state, action, binding)),
+                        Err(err) => problems.push(KeymapProblem::InvalidBinding(view_state, err)),
+                    }
+                }
+            }
+        }
+        // A key that triggers two actions in the same view is ambiguous, so
+        // neither of them fires until the file is fixed.
+        let conflicts: Vec<usize> = (0..bindings.len())
+            .filter(|&i| {
+                bindings.iter().enumerate().any(|(j, (view_state, _, binding))| {
+                    i != j && *view_state == bindings[i].0 && *binding == bindings[i].2
+                })
+            })
+            .collect();
+        for &i in &conflicts {
+            let (view_state, action, binding) = &bindings[i];
+            problems.push(KeymapProblem::Conflict(*view_state, binding.clone(), *action));
+        }
+        let bindings = bindings
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !conflicts.contains(i))
+            .map(|(_, binding)| binding)
+            .collect();
+        Keymap { bindings, problems }
+    }
+
+    pub fn action(&self, view_state: ViewState, key: &Key, modifiers: Modifiers) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(view, _, binding)| *view == view_state && binding.matches(key, modifiers))
+            .map(|(_, action, _)| *action)
+    }
+
+    pub fn binding(&self, view_state: ViewState, action: Action) -> Option<&Binding> {
+        self.bindings
+            .iter()
+            .find(|(view, bound, _)| *view == view_state && *bound == action)
+            .map(|(_, _, binding)| binding)
+    }
+
+    pub fn bindings(&self, view_state: ViewState) -> impl Iterator<Item = (Action, &Binding)> {
+        self.bindings
+            .iter()
+            .filter(move |(view, _, _)| *view == view_state)
+            .map(|(_, action, binding)| (*action, binding))
+    }
+
+    pub fn get_problems(&self) -> &[KeymapProblem] {
+        &self.problems
+    }
+}
+
+pub async fn async_load_keymap() -> Result<Keymap> {
+    let path = config_dir()?.join("keymap.toml");
+    if !fs::try_exists(&path).await? {
+        return Ok(Keymap::default());
+    }
+    let text = fs::read_to_string(path).await?;
+    match toml::from_str(&text) {
+        Ok(file) => Ok(Keymap::from_file(file)),
+        Err(err) => {
+            let mut keymap = Keymap::default();
+            keymap.problems.push(KeymapProblem::Syntax(err.message().to_string()));
+            Ok(keymap)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::keyboard::key::Named;
+
+    use super::{Action, Binding, BindingError, KeyName, Keymap, KeymapProblem};
+    use crate::gui::state::ViewState;
+
+    fn keymap(text: &str) -> Keymap {
+        Keymap::from_file(toml::from_str(text).unwrap())
+    }
+
+    #[test]
+    fn bindings_parse_modifiers_and_keys() {
+        let binding = Binding::parse("Ctrl+Shift+K").unwrap();
+        assert_eq!(binding, Binding { ctrl: true, alt: false, shift: true, key: KeyName::Character("k".to_string()) });
+        let binding = Binding::parse(" cmd + option + f5 ").unwrap();
+        assert_eq!(binding, Binding { ctrl: true, alt: true, shift: false, key: KeyName::Named(Named::F5) });
+        assert_eq!(Binding::parse("alt+delete").unwrap().to_string(), "Alt+Delete");
+        assert_eq!(Binding::parse("Ctrl+g").unwrap().to_string(), "Ctrl+G");
+    }
+
+    #[test]
+    fn malformed_bindings_are_rejected() {
+        assert_eq!(Binding::parse("Ctrl+"), Err(BindingError::NoKey("Ctrl+".to_string())));
+        assert_eq!(Binding::parse("Hyper+K"), Err(BindingError::UnknownModifier("Hyper".to_string(), "Hyper+K".to_string())));
+        assert_eq!(Binding::parse("Ctrl+Foo"), Err(BindingError::UnknownKey("Foo".to_string(), "Ctrl+Foo".to_string())));
+    }
+
+    #[test]
+    fn conflicting_bindings_are_disabled_in_their_view_only() {
+        let keymap = keymap("[encrypt]\nrun = \"Ctrl+O\"\n");
+        assert_eq!(keymap.binding(ViewState::Encrypting, Action::Run), None);
+        assert_eq!(keymap.binding(ViewState::Encrypting, Action::AddFiles), None);
+        assert_eq!(keymap.binding(ViewState::Decrypting, Action::AddFiles), Some(&Binding::parse("Ctrl+O").unwrap()));
+        let conflicts: Vec<Action> = keymap
+            .get_problems()
+            .iter()
+            .filter_map(|problem| match problem {
+                KeymapProblem::Conflict(ViewState::Encrypting, _, action) => Some(*action),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(conflicts, [Action::AddFiles, Action::Run]);
+    }
+
+    #[test]
+    fn keymap_problems_are_collected() {
+        let keymap = keymap("[setting]\nrun = \"Ctrl+R\"\n[decrypt]\nclear_queue = \"Ctrl+Nope\"\n");
+        assert!(keymap.get_problems().contains(&KeymapProblem::Unavailable(ViewState::Setting, Action::Run)));
+        let invalid = KeymapProblem::InvalidBinding(ViewState::Decrypting, BindingError::UnknownKey("Nope".to_string(), "Ctrl+Nope".to_string()));
+        assert!(keymap.get_problems().contains(&invalid));
+        assert_eq!(keymap.binding(ViewState::Decrypting, Action::ClearQueue), None);
+    }
+}
@@ -1,12 +1,32 @@
 use anyhow::Result;
-use iced::{application, event::listen_with};
+use iced::{application, event::{listen_with, Status}, Event, Task};
 use anyhow::Error;
 
-use super::{message::Message, update::update, view::view};
+use crate::core::load_settings;
+
+use super::{message::{load_contacts, load_keymap, report, Message}, state::{Operation, State}, theme::theme, update::update, view::view};
 
 pub fn run() -> Result<()> {
+    // A broken file is reported and left alone, it's only replaced once the
+    // user changes a preference.
+    let (settings, load_error) = match load_settings() {
+        Ok(settings) => (settings, None),
+        Err(err) => (Default::default(), Some(err)),
+    };
+    let system_theme = dark_light::detect();
     application("RSA tool", update, view)
-        .subscription(|_| listen_with(|event, _status, _id| Some(Message::OnEvent(event))))
-        .run()
-        .map_err(|err| Error::new(err))
+        .theme(move |state| theme(state.get_settings().theme, system_theme))
+        .subscription(|_| listen_with(|event, status, _id| match (event, status) {
+            (Event::Keyboard(_), Status::Captured) => None,
+            (event, _) => Some(Message::OnEvent(event)),
+        }))
+        .run_with(move || {
+            let state = State::with_settings(settings);
+            let mut tasks = vec![load_contacts(&state), load_keymap(&state)];
+            if let Some(err) = load_error {
+                tasks.push(Task::done(report(state.get_locale(), Operation::LoadSettings, None, None, Err(err))));
+            }
+            (state, Task::batch(tasks))
+        })
+        .map_err(Error::new)
 }
\ No newline at end of file
@@ -0,0 +1,251 @@
+# Navigation
+tab-setting = Setting
+tab-encrypt = Encrypt
+tab-decrypt = Decrypt
+tab-preferences = Preferences
+shortcuts = Shortcuts
+shortcuts-title = Keyboard shortcuts
+shortcuts-hint = Edit keymap.toml in the config directory to change them.
+close = Close
+cancel = Cancel
+
+# Keyboard actions
+action-generate-keys = Generate both keys
+action-save-keys = Save available keys
+action-switch-view = Next view
+action-add-files = Add files
+action-run = Pick output directory and run
+action-clear-queue = Clear queue
+action-decrypt-from-clipboard = Decrypt from clipboard
+action-cheat-sheet = Show shortcuts
+keymap-unavailable = [{ $view }] { $action } isn't available in this view.
+keymap-no-key = [{ $view }] "{ $binding }" has no key.
+keymap-unknown-modifier = [{ $view }] Unknown modifier "{ $modifier }" in "{ $binding }".
+keymap-unknown-key = [{ $view }] Unknown key "{ $key }" in "{ $binding }".
+keymap-conflict = [{ $view }] { $binding } is bound to more than one action, "{ $action }" is disabled.
+keymap-syntax = keymap.toml: { $error }
+
+# Setting view
+private-key-title = Private key or RSA bit(default: { $bits })
+private-key-ready = Private key is ready! ({ $format })
+private-key-not-ready = Private key isn't ready...
+key-generate-hint = Press { $binding } to generate a { $bits }-bit key pair.
+key-generate-hint-unbound = Use "{ $action }" to generate a { $bits }-bit key pair.
+passphrase-placeholder = Passphrase (encrypted keys only)
+public-key-title = Public key
+public-key-ready = Public key is ready! ({ $format })
+public-key-not-ready = Public key isn't ready...
+copy = Copy
+copy-fingerprint = Copy fingerprint
+paste = Paste
+contacts-title = Contacts (drop public keys here)
+contact-name = Name
+contact-email = Email
+remove = Remove
+
+# Encrypt and Decrypt views
+encrypt-title = RSA tool - Encrypt
+decrypt-title = RSA tool - Decrypt
+encrypt-to = Encrypt to...
+decrypt-to = Decrypt to...
+plaintext-placeholder = Plaintext
+encrypt-text = Encrypt text
+decrypt-text = Decrypt text
+from-clipboard = From clipboard
+text-failed = Failed! { $error }
+job-queued = Queued
+job-running = Working...
+job-done = Saved to { $path }
+job-done-warning = Saved to { $path }, but { $warning }
+job-failed = Failed: { $error }
+jobs-finished = Finished: { $succeeded } succeeded, { $failed } failed
+output-encoding = Output encoding
+recipient-placeholder = Recipient (default: public key editor)
+recipient-clear = Use public key editor
+
+# Preferences view
+preferences-title = RSA tool - Preferences
+pref-key-size = Default key size
+pref-exponent = Public exponent
+pref-key-transport = Key transport
+pref-aead = Payload cipher
+pref-compression = Compression
+pref-output-encoding = Output encoding
+pref-output-dir = Output directory
+pref-output-dir-ask = Ask every time
+pref-theme = Theme
+pref-language = Language
+choose = Choose...
+clear = Clear
+theme-system = System
+theme-light = Light
+theme-dark = Dark
+theme-high-contrast = High contrast
+language-system = System
+language-english = English
+language-chinese = 中文
+
+# Notifications and history
+history = History
+history-title = Operation history
+history-empty = Nothing has happened yet.
+history-succeeded = Succeeded
+history-replaced = Replaced
+history-cancelled = Cancelled
+history-details = Input: { $input } | Key: { $fingerprint } | Output: { $output }
+toast-succeeded = { $operation } succeeded.
+toast-saved = { $operation }: saved to { $path }
+toast-replaced = { $operation }: replaced { $path }
+toast-cancelled = { $operation } cancelled.
+toast-failed = { $operation } failed: { $error }
+op-generate-key = Generate key
+op-save-private-key = Save private key
+op-save-public-key = Save public key
+op-import-key = Import key
+op-encrypt = Encrypt file
+op-decrypt = Decrypt file
+op-encrypt-text = Encrypt text
+op-decrypt-text = Decrypt text
+op-load-contacts = Load contacts
+op-save-contacts = Save contacts
+op-load-keymap = Load key bindings
+op-load-settings = Load preferences
+op-save-settings = Save preferences
+op-restrict-key-file = Restrict key file permissions
+op-edit-recipients = Edit recipients
+# Ciphertext inspector
+inspect = Inspect
+inspect-file = Inspect file...
+inspect-title = Container details
+inspect-failed = Cannot inspect this file: { $error }
+inspect-version = Format version
+inspect-created = Created
+inspect-payload = Payload
+inspect-payload-file = File
+inspect-payload-archive = Directory archive
+inspect-payload-text = Text
+inspect-aead = Encryption
+inspect-chunks = Chunks
+inspect-chunks-value = { $count } × { $size } bytes
+inspect-payload-size = Payload size
+inspect-payload-size-value = { $size } bytes
+inspect-recipients = Recipients ({ $count })
+inspect-owner-private-key = Loaded private key
+inspect-owner-public-key = Public key editor
+inspect-owner-contact = Contact: { $name }
+inspect-owner-unknown = Not in the keystore
+drop-missing-key = No loaded or stored private key can open it. { $count ->
+    [one] It needs the key with fingerprint { $fingerprints }.
+   *[other] It needs one of the keys with fingerprints { $fingerprints }.
+}
+drop-view-busy = Finish or clear the pending files and text first, dropping an encrypted file switches to decryption.
+# Existing output files
+job-conflict = waiting, { $path } already exists
+job-skipped = Skipped, { $path } already exists
+conflict-title = File already exists
+conflict-exists = { $path } already exists. Replace it?
+conflict-replace = Replace
+conflict-keep-both = Keep both
+conflict-skip = Skip
+conflict-apply-to-all = Do the same for the other existing files
+# Key file permissions
+private-key-exposed = { $path } can be read by other users (mode { $mode }).
+restrict-permissions = Restrict to owner
+# Secure deletion
+shred-originals = Securely delete originals after encryption
+shred-needs-private-key = Load the matching private key to verify the output first.
+shred-title = Securely delete originals?
+shred-explanation = After each file is encrypted, the result is decrypted and compared with the original by SHA-256. Only if they match is the original overwritten with random data and deleted. Directories are never deleted.
+shred-warning = Overwriting is not reliable on copy-on-write file systems (Btrfs, ZFS, APFS) or flash storage such as SSDs and USB sticks, where old copies of the data may survive.
+shred-enable = Delete originals
+shred-failed = Encrypted to { $path }, secure deletion failed: { $error }
+shred-original-kept = the original was not deleted, only regular files are shredded.
+# Compression
+compression-off = Off
+compression-auto = Auto (skip incompressible files)
+compression-zstd = zstd
+compression-deflate = Deflate
+compression-none = None
+inspect-compression = Compression
+# Cipher benchmark
+benchmark = Benchmark...
+benchmark-title = Cipher benchmark
+benchmark-hint = Encryption throughput of each payload cipher on this machine.
+benchmark-running = Measuring...
+benchmark-failed = Benchmark failed: { $error }
+benchmark-throughput = { $speed } MiB/s
+benchmark-run-again = Run again
+recipients-edit = Edit recipients...
+recipients-need-key = Load the private key of a recipient to edit recipients.
+recipients-current = Current recipients
+recipients-add = Add recipients
+recipients-transport = New recipients get the content key wrapped with { $transport }.
+recipients-removed-hint = Removed recipients can still open any copy of this file they already have.
+recipients-empty = Keep at least one recipient.
+recipients-save = Save recipients
+# File dialogs
+dialog-save-private-key = Save private key
+dialog-save-public-key = Save public key
+dialog-private-key-filter = Private key PEM file
+dialog-public-key-filter = Public key PEM file
+dialog-pick-files = Pick files
+dialog-pick-file = Pick a file
+dialog-pick-output-dir = Pick output directory
+# Key parsing
+key-line = Line { $line }: { $problem }
+key-passphrase-required = The key is encrypted, a passphrase is required!
+key-jwk-bad-base64 = Broken base64url in JWK: { $error }
+key-jwk-not-rsa = JWK key type "{ $kty }" isn't RSA!
+key-jwk-missing-primes = JWK private keys need the "p" and "q" members!
+key-openssh-not-rsa = The OpenSSH key isn't an RSA key!
+key-unsupported-pem-label = Unsupported PEM label "{ $label }"!
+key-unrecognized = Unrecognized key format!
+key-empty = The key is empty!
+key-missing-begin = Missing the "-----BEGIN ...-----" line, the start of the key was cut off.
+key-malformed-begin = The BEGIN line is malformed.
+key-missing-end = Missing the "-----END ...-----" line, the key looks truncated.
+key-end-mismatch = The END label doesn't match "BEGIN { $label }".
+key-legacy-encrypted-pem = Legacy OpenSSL-encrypted PEM isn't supported, convert it to encrypted PKCS#8.
+key-invalid-base64-line = This line contains characters that aren't valid base64.
+key-short-line = This line is shorter than the others, part of the key may be missing.
+key-long-line = This line is longer than the others, two lines may have been joined.
+key-incomplete-body = The base64 body is incomplete, the last line may be cut off.
+key-editor-empty = The editor is empty.
+key-invalid-pem-block = Not a valid "{ $label }" block: { $error }
+key-invalid-jwk = Invalid JWK: { $error }
+key-invalid = { $error }
+key-public-in-private-editor = This is a public key ({ $format }), move it to the public key editor.
+key-private-in-public-editor = This is a private key ({ $format }), move it to the private key editor.
+# Encryption errors
+error-legacy-ciphertext = No container header, this may be a legacy raw RSA ciphertext!
+error-archive-in-text = The message holds a directory archive, decrypt it as a file!
+error-text-not-utf8 = The message isn't valid UTF-8 text!
+error-invalid-archive-root = Invalid archive root!
+error-invalid-file-name = Invalid file name!
+error-compression-ratio = { $compression } would shrink this data further than decryption accepts, choose Auto or another compression!
+# Command line
+cli-usage = Usage:
+    rsa-tool                                  Open the graphical interface
+    rsa-tool encrypt [options] <path>...      Encrypt files and directories
+    rsa-tool help                             Show this help
+
+    Options for encrypt:
+      --public-key <file>    Key to encrypt to (required)
+      --output-dir <dir>     Where to write the output (default: preferences, or next to each file)
+      --shred                Securely delete each original once its output is verified
+      --private-key <file>   Matching private key, needed by --shred to verify the output
+
+    Everything else is taken from the preferences.
+cli-missing-value = { $option } needs a value.
+cli-unknown-option = Unknown option { $option }.
+cli-missing-public-key = --public-key is required.
+cli-missing-paths = Nothing to encrypt, give at least one path.
+cli-shred-needs-private-key = --shred needs --private-key to verify the output first.
+cli-key-mismatch = The private key doesn't belong to the public key.
+cli-not-private-key = { $path } holds a public key, --private-key needs the private one.
+cli-shred-confirm = Type "yes" to delete the originals:
+cli-shred-declined = Nothing was encrypted or deleted.
+cli-encrypted = Encrypted { $input } to { $output }
+cli-encrypted-warning = Encrypted { $input } to { $output }, but { $warning }
+cli-shredded = Encrypted { $input } to { $output } and deleted the original
+cli-failed = { $input }: { $error }
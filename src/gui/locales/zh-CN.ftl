@@ -0,0 +1,248 @@
+# Navigation
+tab-setting = 密钥
+tab-encrypt = 加密
+tab-decrypt = 解密
+tab-preferences = 偏好设置
+shortcuts = 快捷键
+shortcuts-title = 键盘快捷键
+shortcuts-hint = 编辑配置目录中的 keymap.toml 以修改快捷键。
+close = 关闭
+cancel = 取消
+
+# Keyboard actions
+action-generate-keys = 生成密钥对
+action-save-keys = 保存现有密钥
+action-switch-view = 下一个视图
+action-add-files = 添加文件
+action-run = 选择输出目录并运行
+action-clear-queue = 清空队列
+action-decrypt-from-clipboard = 解密剪贴板内容
+action-cheat-sheet = 显示快捷键
+keymap-unavailable = [{ $view }] { $action } 在此视图中不可用。
+keymap-no-key = [{ $view }] “{ $binding }”缺少按键。
+keymap-unknown-modifier = [{ $view }] “{ $binding }”中的修饰键“{ $modifier }”无法识别。
+keymap-unknown-key = [{ $view }] “{ $binding }”中的按键“{ $key }”无法识别。
+keymap-conflict = [{ $view }] { $binding } 绑定了多个操作，“{ $action }”已停用。
+keymap-syntax = keymap.toml：{ $error }
+
+# Setting view
+private-key-title = 私钥或 RSA 位数（默认：{ $bits }）
+private-key-ready = 私钥已就绪！（{ $format }）
+private-key-not-ready = 私钥尚未就绪……
+key-generate-hint = 按 { $binding } 生成 { $bits } 位密钥对。
+key-generate-hint-unbound = 使用“{ $action }”生成 { $bits } 位密钥对。
+passphrase-placeholder = 口令（仅用于加密的密钥）
+public-key-title = 公钥
+public-key-ready = 公钥已就绪！（{ $format }）
+public-key-not-ready = 公钥尚未就绪……
+copy = 复制
+copy-fingerprint = 复制指纹
+paste = 粘贴
+contacts-title = 联系人（将公钥拖放到此处）
+contact-name = 姓名
+contact-email = 邮箱
+remove = 删除
+
+# Encrypt and Decrypt views
+encrypt-title = RSA 工具 - 加密
+decrypt-title = RSA 工具 - 解密
+encrypt-to = 加密到……
+decrypt-to = 解密到……
+plaintext-placeholder = 明文
+encrypt-text = 加密文本
+decrypt-text = 解密文本
+from-clipboard = 从剪贴板
+text-failed = 失败！{ $error }
+job-queued = 排队中
+job-running = 处理中……
+job-done = 已保存到 { $path }
+job-done-warning = 已保存到 { $path }，但{ $warning }
+job-failed = 失败：{ $error }
+jobs-finished = 已完成：{ $succeeded } 个成功，{ $failed } 个失败
+output-encoding = 输出编码
+recipient-placeholder = 接收者（默认：公钥编辑器中的公钥）
+recipient-clear = 改用公钥编辑器中的公钥
+
+# Preferences view
+preferences-title = RSA 工具 - 偏好设置
+pref-key-size = 默认密钥长度
+pref-exponent = 公钥指数
+pref-key-transport = 密钥封装方式
+pref-aead = 内容加密算法
+pref-compression = 压缩
+pref-output-encoding = 输出编码
+pref-output-dir = 输出目录
+pref-output-dir-ask = 每次询问
+pref-theme = 主题
+pref-language = 语言
+choose = 选择……
+clear = 清除
+theme-system = 跟随系统
+theme-light = 浅色
+theme-dark = 深色
+theme-high-contrast = 高对比度
+language-system = 跟随系统
+language-english = English
+language-chinese = 中文
+
+# Notifications and history
+history = 历史
+history-title = 操作历史
+history-empty = 暂无操作记录。
+history-succeeded = 成功
+history-replaced = 已替换
+history-cancelled = 已取消
+history-details = 输入：{ $input } | 密钥：{ $fingerprint } | 输出：{ $output }
+toast-succeeded = { $operation }成功。
+toast-saved = { $operation }：已保存到 { $path }
+toast-replaced = { $operation }：已替换 { $path }
+toast-cancelled = { $operation }已取消。
+toast-failed = { $operation }失败：{ $error }
+op-generate-key = 生成密钥
+op-save-private-key = 保存私钥
+op-save-public-key = 保存公钥
+op-import-key = 导入密钥
+op-encrypt = 加密文件
+op-decrypt = 解密文件
+op-encrypt-text = 加密文本
+op-decrypt-text = 解密文本
+op-load-contacts = 加载联系人
+op-save-contacts = 保存联系人
+op-load-keymap = 加载快捷键
+op-load-settings = 加载偏好设置
+op-save-settings = 保存偏好设置
+op-restrict-key-file = 限制密钥文件权限
+op-edit-recipients = 编辑接收者
+# Ciphertext inspector
+inspect = 查看
+inspect-file = 查看文件...
+inspect-title = 容器详情
+inspect-failed = 无法查看该文件：{ $error }
+inspect-version = 格式版本
+inspect-created = 创建时间
+inspect-payload = 内容类型
+inspect-payload-file = 文件
+inspect-payload-archive = 目录归档
+inspect-payload-text = 文本
+inspect-aead = 加密算法
+inspect-chunks = 分块
+inspect-chunks-value = { $count } × { $size } 字节
+inspect-payload-size = 内容大小
+inspect-payload-size-value = { $size } 字节
+inspect-recipients = 接收者（{ $count }）
+inspect-owner-private-key = 已加载的私钥
+inspect-owner-public-key = 公钥编辑器
+inspect-owner-contact = 联系人：{ $name }
+inspect-owner-unknown = 不在密钥库中
+drop-missing-key = 已加载或已存储的私钥都无法打开它，需要指纹为 { $fingerprints } 的私钥之一。
+drop-view-busy = 请先处理或清除待处理的文件和文本，拖入加密文件会切换到解密视图。
+# Existing output files
+job-conflict = 等待中，{ $path } 已存在
+job-skipped = 已跳过，{ $path } 已存在
+conflict-title = 文件已存在
+conflict-exists = { $path } 已存在，是否替换？
+conflict-replace = 替换
+conflict-keep-both = 保留两者
+conflict-skip = 跳过
+conflict-apply-to-all = 对其他已存在的文件执行相同操作
+# Key file permissions
+private-key-exposed = 其他用户可以读取 { $path }（权限 { $mode }）。
+restrict-permissions = 仅限所有者
+# Secure deletion
+shred-originals = 加密后安全删除原文件
+shred-needs-private-key = 请先加载对应的私钥，以便校验加密结果。
+shred-title = 安全删除原文件？
+shred-explanation = 每个文件加密后，会先解密结果并用 SHA-256 与原文件比对，一致时才用随机数据覆盖原文件并删除。目录不会被删除。
+shred-warning = 在写时复制文件系统（Btrfs、ZFS、APFS）或固态硬盘、U 盘等闪存设备上，覆盖并不可靠，旧数据可能仍然残留。
+shred-enable = 删除原文件
+shred-failed = 已加密到 { $path }，安全删除失败：{ $error }
+shred-original-kept = 原文件未被删除，只有普通文件会被安全删除。
+# Compression
+compression-off = 关闭
+compression-auto = 自动（跳过无法压缩的文件）
+compression-zstd = zstd
+compression-deflate = Deflate
+compression-none = 无
+inspect-compression = 压缩
+# Cipher benchmark
+benchmark = 性能测试...
+benchmark-title = 加密算法性能测试
+benchmark-hint = 本机上各内容加密算法的加密吞吐量。
+benchmark-running = 正在测量...
+benchmark-failed = 性能测试失败：{ $error }
+benchmark-throughput = { $speed } MiB/s
+benchmark-run-again = 重新测试
+recipients-edit = 编辑接收者...
+recipients-need-key = 请加载某个接收者的私钥以编辑接收者。
+recipients-current = 当前接收者
+recipients-add = 添加接收者
+recipients-transport = 新接收者的内容密钥将使用 { $transport } 封装。
+recipients-removed-hint = 被移除的接收者仍可打开其已持有的此文件副本。
+recipients-empty = 请至少保留一个接收者。
+recipients-save = 保存接收者
+# File dialogs
+dialog-save-private-key = 保存私钥
+dialog-save-public-key = 保存公钥
+dialog-private-key-filter = 私钥 PEM 文件
+dialog-public-key-filter = 公钥 PEM 文件
+dialog-pick-files = 选择文件
+dialog-pick-file = 选择一个文件
+dialog-pick-output-dir = 选择输出目录
+# Key parsing
+key-line = 第 { $line } 行：{ $problem }
+key-passphrase-required = 密钥已加密，需要输入口令！
+key-jwk-bad-base64 = JWK 中的 base64url 已损坏：{ $error }
+key-jwk-not-rsa = JWK 密钥类型“{ $kty }”不是 RSA！
+key-jwk-missing-primes = JWK 私钥需要“p”和“q”成员！
+key-openssh-not-rsa = 该 OpenSSH 密钥不是 RSA 密钥！
+key-unsupported-pem-label = 不支持的 PEM 标签“{ $label }”！
+key-unrecognized = 无法识别的密钥格式！
+key-empty = 密钥为空！
+key-missing-begin = 缺少“-----BEGIN ...-----”行，密钥开头被截断。
+key-malformed-begin = BEGIN 行格式错误。
+key-missing-end = 缺少“-----END ...-----”行，密钥似乎被截断。
+key-end-mismatch = END 标签与“BEGIN { $label }”不匹配。
+key-legacy-encrypted-pem = 不支持旧式 OpenSSL 加密的 PEM，请将其转换为加密的 PKCS#8。
+key-invalid-base64-line = 此行包含无效的 base64 字符。
+key-short-line = 此行比其他行短，密钥可能缺失了一部分。
+key-long-line = 此行比其他行长，可能有两行被合并了。
+key-incomplete-body = base64 内容不完整，最后一行可能被截断。
+key-editor-empty = 编辑器为空。
+key-invalid-pem-block = 无效的“{ $label }”块：{ $error }
+key-invalid-jwk = 无效的 JWK：{ $error }
+key-invalid = { $error }
+key-public-in-private-editor = 这是公钥（{ $format }），请移到公钥编辑器。
+key-private-in-public-editor = 这是私钥（{ $format }），请移到私钥编辑器。
+# Encryption errors
+error-legacy-ciphertext = 没有容器头，这可能是旧式的原始 RSA 密文！
+error-archive-in-text = 该消息包含目录归档，请作为文件解密！
+error-text-not-utf8 = 该消息不是有效的 UTF-8 文本！
+error-invalid-archive-root = 无效的归档根目录！
+error-invalid-file-name = 无效的文件名！
+error-compression-ratio = { $compression } 对这些数据的压缩率超出了解密所能接受的范围，请选择“自动”或其他压缩方式！
+# 命令行
+cli-usage = 用法：
+    rsa-tool                                  打开图形界面
+    rsa-tool encrypt [选项] <路径>...         加密文件和目录
+    rsa-tool help                             显示此帮助
+
+    encrypt 的选项：
+      --public-key <文件>    加密所用的公钥（必需）
+      --output-dir <目录>    输出位置（默认：偏好设置，或各文件所在目录）
+      --shred                输出验证无误后安全删除原文件
+      --private-key <文件>   对应的私钥，--shred 需要它来验证输出
+
+    其余选项取自偏好设置。
+cli-missing-value = { $option } 需要一个值。
+cli-unknown-option = 未知选项 { $option }。
+cli-missing-public-key = 必须指定 --public-key。
+cli-missing-paths = 没有要加密的内容，请至少给出一个路径。
+cli-shred-needs-private-key = --shred 需要 --private-key 来先验证输出。
+cli-key-mismatch = 私钥与公钥不匹配。
+cli-not-private-key = { $path } 是公钥，--private-key 需要私钥。
+cli-shred-confirm = 输入 "yes" 以删除原文件：
+cli-shred-declined = 没有加密或删除任何文件。
+cli-encrypted = 已将 { $input } 加密为 { $output }
+cli-encrypted-warning = 已将 { $input } 加密为 { $output }，但{ $warning }
+cli-shredded = 已将 { $input } 加密为 { $output } 并删除了原文件
+cli-failed = { $input }: { $error }
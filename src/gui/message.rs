@@ -1,13 +1,18 @@
-use std::path::PathBuf;
+use std::{fmt::{self, Display}, future::Future, path::PathBuf, time::Duration};
 
-use anyhow::Result;
-use iced::{widget::text_editor, Event, Task};
+use anyhow::{Error, Result};
+use iced::{clipboard, futures::{stream, StreamExt}, widget::text_editor, Event, Task};
 use rfd::FileHandle;
-use rsa::{pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey}, RsaPrivateKey, RsaPublicKey};
+use rsa::{RsaPrivateKey, RsaPublicKey};
 
-use crate::core::{async_decrypt, async_encrypt, async_generate_priv_key_from_bits, async_get_data, async_pick_file, async_priv_key_to_pub_key, async_save_decrypted_file, async_save_encrypted_file, async_save_private_pem_file, async_save_public_pem_file, async_to_priv_key, async_to_pub_key};
+use crate::core::{async_benchmark_aead, async_decrypt_file_to, async_exposed_mode, async_decrypt_text, async_encrypt_file_to, async_encrypt_text, async_find_stored_private_key, async_generate_priv_key_from_bits, async_get_key, async_inspect_file, async_load_contacts, async_pick_file, async_pick_files, async_pick_output_dir, async_priv_key_to_pub_key, async_save_contacts, async_pick_private_pem_file, async_pick_public_pem_file, async_restrict_to_owner, async_rewrap_file, async_save_private_pem_file, async_save_public_pem_file, async_save_settings, async_shred_verified, async_to_priv_key, async_to_pub_key, fingerprint, AeadAlgorithm, AlreadyExists, Cancelled, Contact, DetectedKey, Inspection, KeyFormat, OutputEncoding, Overwrite, Settings};
 
-use super::state::State;
+use super::{i18n::{tr, tr_args, tr_error, Locale}, keymap::{async_load_keymap, Action, Keymap}, state::{Conflict, ConflictChoice, ConflictSource, KeyFile, KeyOwner, Operation, Outcome, Report, State, ViewState}};
+
+const PARSE_DEBOUNCE: Duration = Duration::from_millis(300);
+const MAX_CONCURRENT_JOBS: usize = 4;
+const CLIPBOARD_SECRET_TIMEOUT: Duration = Duration::from_secs(30);
+const TOAST_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -15,139 +20,659 @@ pub enum Message {
     OnPrivateKeyAction(text_editor::Action),
     OnPublicKeyAction(text_editor::Action),
     FillPrivateKey(RsaPrivateKey),
-    CleanPrivateKey,
-    SetPrivateKey(RsaPrivateKey),
-    FillPublicKey(RsaPublicKey),
-    CleanPublicKey,
-    SetPublicKey(RsaPublicKey),
-    SetFilePath(PathBuf),
-    SaveEncryptResult(Option<Vec<u8>>),
-    SaveDecryptResult(Option<Vec<u8>>),
+    LoadPrivateKeyFile(PathBuf, RsaPrivateKey),
+    SetExposedKeyFile(Option<(PathBuf, u32)>),
+    RestrictKeyFile,
+    CleanPrivateKey(u64, String),
+    SetPrivateKey(u64, RsaPrivateKey, KeyFormat),
+    FillPublicKey(u64, RsaPublicKey),
+    CleanPublicKey(u64, String),
+    SetPublicKey(u64, RsaPublicKey, KeyFormat),
+    SetPassphrase(String),
+    AddJobs(Vec<PathBuf>),
+    RunJobs(PathBuf),
+    FinishJob(usize, Result<(PathBuf, Option<String>), String>),
+    Conflict(Conflict),
+    ResolveConflict(ConflictChoice),
+    SetApplyToAllConflicts(bool),
+    SetShredOriginals(bool),
+    ToggleBenchmark,
+    RunBenchmark,
+    SetBenchmark(Result<Vec<(AeadAlgorithm, f64)>, String>),
+    ConfirmShred(bool),
+    WritePemFile(KeyFile, PathBuf, Overwrite),
+    OnTextInputAction(text_editor::Action),
+    OnTextOutputAction(text_editor::Action),
+    RunText,
+    SelectOutputEncoding(OutputEncoding),
+    CopyToClipboard(String),
+    CopyTextOutput,
+    PasteTextInput,
+    SetTextInput(Option<String>),
+    PastePublicKey,
+    SetPublicKeyText(Option<String>),
+    DecryptFromClipboard,
+    DecryptText(Option<String>),
+    ExpireClipboard(String),
+    FinishText(Operation, Option<String>, Result<String, String>),
+    SetContacts(Vec<Contact>),
+    LoadPublicKeyFile(Contact),
+    RemoveContact(String),
+    SetContactName(usize, String),
+    SetContactEmail(usize, String),
+    ContactsSaved(Result<(), String>),
+    SelectRecipient(Contact),
+    ClearRecipient,
+    SelectView(ViewState),
+    SwitchView,
+    GenerateKeys,
+    SaveKeys,
+    PickFiles,
+    PickOutputDir,
+    ClearJobs,
+    SetKeymap(Keymap),
+    SaveSettings(Settings),
+    PickDefaultOutputDir,
+    ToggleCheatSheet,
+    Report(Report),
+    DismissToast(u64),
+    ToggleHistory,
+    ClearHistory,
+    PickInspectFile,
+    InspectFile(PathBuf),
+    SetInspection(PathBuf, Result<Inspection, String>),
+    CloseInspection,
+    EditRecipients,
+    SelectRecipientKey(String, bool),
+    SaveRecipients,
+    CloseRecipientsEditor,
+    FinishRecipients(PathBuf, Result<PathBuf, String>),
+    DropFile(PathBuf),
+    DropContainer(PathBuf, Inspection),
+    UseStoredKey(PathBuf, PathBuf, RsaPrivateKey),
+    MissingKey(PathBuf, Inspection),
     NoThingToDo,
 }
 
-
-pub fn generate_priv_key_from_bits(bit: usize) -> Task<Message> {
-    let f =|result| match result {
+pub fn generate_priv_key_from_bits(state: &State, bit: usize, exponent: u64) -> Task<Message> {
+    let locale = state.get_locale();
+    let f = move |result| match result {
         Ok(private_key) => Message::FillPrivateKey(private_key),
-        Err(_) => Message::NoThingToDo,
+        Err(err) => report(locale, Operation::GenerateKey, None, None, Err(err)),
     };
-    Task::perform(async_generate_priv_key_from_bits(bit), f)
+    Task::perform(async_generate_priv_key_from_bits(bit, exponent), f)
+}
+
+// A bare number is a key size for generation, not a key to parse.
+fn generate_hint(state: &State, bits: usize) -> String {
+    match state.get_keymap().binding(ViewState::Setting, Action::GenerateKeys) {
+        Some(binding) => state.tr_args("key-generate-hint", &[("binding", binding.to_string().into()), ("bits", bits.into())]),
+        None => state.tr_args("key-generate-hint-unbound", &[("action", state.tr(Action::GenerateKeys.label_id()).into()), ("bits", bits.into())]),
+    }
 }
 
 pub fn text_to_priv_key(state: &mut State) -> Task<Message> {
-    let f =|result| match result {
-        Ok(private_key) => Message::SetPrivateKey(private_key),
-        Err(_) => Message::CleanPrivateKey,
+    let generation = state.next_priv_key_generation();
+    let text = state.get_priv_key_text_content().text();
+    if let Ok(bits) = text.trim().parse::<usize>() {
+        return Task::done(Message::CleanPrivateKey(generation, generate_hint(state, bits)));
+    }
+    let passphrase = state.get_passphrase();
+    let locale = state.get_locale();
+    let future = async move {
+        tokio::time::sleep(PARSE_DEBOUNCE).await;
+        async_to_priv_key(text, passphrase).await
+    };
+    let f = move |result: Result<(RsaPrivateKey, KeyFormat)>| match result {
+        Ok((private_key, format)) => Message::SetPrivateKey(generation, private_key, format),
+        Err(err) => Message::CleanPrivateKey(generation, tr_error(locale, &err)),
     };
-    Task::perform(async_to_priv_key(state.get_priv_key_text_content().text()), f)
+    let (task, handle) = Task::perform(future, f).abortable();
+    state.set_priv_key_parse(handle);
+    task
+}
+
+pub fn generate_keys(state: &State) -> Task<Message> {
+    let bit = state
+        .get_priv_key_text_content()
+        .text()
+        .trim()
+        .trim_matches('g')
+        .trim_matches('G')
+        .parse()
+        .unwrap_or(state.get_settings().key_size);
+    generate_priv_key_from_bits(state, bit, state.get_settings().exponent)
+}
+
+pub fn save_keys(state: &mut State) -> Task<Message> {
+    state.start_key_save();
+    match (state.get_private_key().is_some(), state.get_public_key().is_some()) {
+        (true, true) => save_private_key(state).chain(save_public_key(state)),
+        (true, false) => save_private_key(state),
+        (false, true) => save_public_key(state),
+        (false, false) => Task::none(),
+    }
 }
 
 pub fn save_private_key(state: &State) -> Task<Message> {
-    let future = async_save_private_pem_file(state.get_priv_key_text_content().text());
-    Task::perform(future, |_| Message::NoThingToDo)
+    let fingerprint = state.get_private_key().map(|private_key| fingerprint(&private_key.to_public_key()));
+    let locale = state.get_locale();
+    let f = move |result: Result<PathBuf>| match result {
+        Ok(path) => Message::WritePemFile(KeyFile::Private, path, Overwrite::Ask),
+        Err(err) => report(locale, Operation::SavePrivateKey, None, fingerprint.clone(), Err(err)),
+    };
+    Task::perform(async_pick_private_pem_file(state.tr("dialog-save-private-key"), state.tr("dialog-private-key-filter")), f)
 }
 
 pub fn text_to_pub_key(state: &mut State) -> Task<Message> {
-    let f =|result| match result {
-        Ok(public_key) => Message::SetPublicKey(public_key),
-        Err(_) => Message::CleanPublicKey,
+    let generation = state.next_pub_key_generation();
+    let text = state.get_pub_key_text_content().text();
+    let locale = state.get_locale();
+    let future = async move {
+        tokio::time::sleep(PARSE_DEBOUNCE).await;
+        async_to_pub_key(text).await
+    };
+    let f = move |result: Result<(RsaPublicKey, KeyFormat)>| match result {
+        Ok((public_key, format)) => Message::SetPublicKey(generation, public_key, format),
+        Err(err) => Message::CleanPublicKey(generation, tr_error(locale, &err)),
     };
-    Task::perform(async_to_pub_key(state.get_pub_key_text_content().text()), f)
+    let (task, handle) = Task::perform(future, f).abortable();
+    state.set_pub_key_parse(handle);
+    task
 }
 
 pub fn save_public_key(state: &State) -> Task<Message> {
-    let future = async_save_public_pem_file(state.get_pub_key_text_content().text());
-    Task::perform(future, |_| Message::NoThingToDo)
+    let fingerprint = state.get_public_key().map(|public_key| fingerprint(&public_key));
+    let locale = state.get_locale();
+    let f = move |result: Result<PathBuf>| match result {
+        Ok(path) => Message::WritePemFile(KeyFile::Public, path, Overwrite::Ask),
+        Err(err) => report(locale, Operation::SavePublicKey, None, fingerprint.clone(), Err(err)),
+    };
+    Task::perform(async_pick_public_pem_file(state.tr("dialog-save-public-key"), state.tr("dialog-public-key-filter")), f)
+}
+
+fn key_file_details(state: &State, key_file: KeyFile) -> (Operation, String, Option<String>) {
+    match key_file {
+        KeyFile::Private => (
+            Operation::SavePrivateKey,
+            state.get_priv_key_text_content().text(),
+            state.get_private_key().map(|private_key| fingerprint(&private_key.to_public_key())),
+        ),
+        KeyFile::Public => (
+            Operation::SavePublicKey,
+            state.get_pub_key_text_content().text(),
+            state.get_public_key().map(|public_key| fingerprint(&public_key)),
+        ),
+    }
+}
+
+pub fn write_pem_file(state: &State, key_file: KeyFile, path: PathBuf, overwrite: Overwrite) -> Task<Message> {
+    let (operation, text, fingerprint) = key_file_details(state, key_file);
+    let target = path.clone();
+    let locale = state.get_locale();
+    let future = async move {
+        match key_file {
+            KeyFile::Private => async_save_private_pem_file(target, text, overwrite).await,
+            KeyFile::Public => async_save_public_pem_file(target, text, overwrite).await,
+        }
+    };
+    let f = move |result: Result<PathBuf>| match result {
+        Err(err) if err.is::<AlreadyExists>() => Message::Conflict(Conflict { source: ConflictSource::KeyFile(key_file), path: path.clone() }),
+        result => report(locale, operation, None, fingerprint.clone(), result.map(Some)),
+    };
+    Task::perform(future, f)
 }
 
 pub fn priv_key_to_pub_key_and_fill(state: &mut State) -> Task<Message> {
+    let generation = state.get_priv_key_generation();
     let future = async_priv_key_to_pub_key(state.get_private_key().unwrap());
-    let f = |result: Result<RsaPublicKey>| Message::FillPublicKey(result.unwrap());
+    let f = move |result: Result<RsaPublicKey>| Message::FillPublicKey(generation, result.unwrap());
     Task::perform(future, f)
 }
 
-pub fn get_key(path: PathBuf) -> Task<Message> {
-    let future = async_get_data(path);
-    let f = |result| {
-        match result {
-            Ok(data) => {
-                match String::from_utf8(data) {
-                    Ok(text) => match (RsaPrivateKey::from_pkcs1_pem(&text), RsaPublicKey::from_pkcs1_pem(&text)) {
-                        (Ok(private_key), _) => Message::FillPrivateKey(private_key),
-                        (_, Ok(public_key)) => Message::FillPublicKey(public_key),
-                        _ => Message::NoThingToDo,
-                    }
-                    Err(_) => Message::NoThingToDo,
-                }
-            }
-            Err(_) => Message::NoThingToDo
+pub fn get_key(state: &State, path: PathBuf) -> Task<Message> {
+    let name = path
+        .file_stem()
+        .and_then(|os_str| os_str.to_str())
+        .unwrap_or("")
+        .to_string();
+    let future = async_get_key(path.clone(), state.get_passphrase());
+    let locale = state.get_locale();
+    let f = move |result| match result {
+        Ok((DetectedKey::Private(private_key), _)) => Message::LoadPrivateKeyFile(path.clone(), *private_key.clone()),
+        Ok((DetectedKey::Public(public_key), _)) => Message::LoadPublicKeyFile(Contact::new(name.clone(), String::new(), public_key)),
+        Err(err) => report(locale, Operation::ImportKey, Some(path.clone()), None, Err(err)),
+    };
+    Task::perform(future, f)
+}
+
+pub fn check_key_file(path: PathBuf) -> Task<Message> {
+    let future = async_exposed_mode(path.clone());
+    let f = move |result: Result<Option<u32>>| match result {
+        Ok(mode) => Message::SetExposedKeyFile(mode.map(|mode| (path.clone(), mode))),
+        Err(_) => Message::NoThingToDo,
+    };
+    Task::perform(future, f)
+}
+
+pub fn restrict_key_file(state: &State) -> Task<Message> {
+    match state.get_exposed_key_file() {
+        Some((path, _)) => {
+            let future = async_restrict_to_owner(path.clone());
+            let path = path.clone();
+            let locale = state.get_locale();
+            let f = move |result: Result<()>| match result {
+                Ok(_) => Message::SetExposedKeyFile(None),
+                Err(err) => report(locale, Operation::RestrictKeyFile, Some(path.clone()), None, Err(err)),
+            };
+            Task::perform(future, f)
         }
+        None => Task::none(),
+    }
+}
+
+pub fn load_contacts(state: &State) -> Task<Message> {
+    let locale = state.get_locale();
+    let f = move |result| match result {
+        Ok(contacts) => Message::SetContacts(contacts),
+        Err(err) => report(locale, Operation::LoadContacts, None, None, Err(err)),
+    };
+    Task::perform(async_load_contacts(), f)
+}
+
+pub fn load_keymap(state: &State) -> Task<Message> {
+    let locale = state.get_locale();
+    let f = move |result| match result {
+        Ok(keymap) => Message::SetKeymap(keymap),
+        Err(err) => report(locale, Operation::LoadKeymap, None, None, Err(err)),
+    };
+    Task::perform(async_load_keymap(), f)
+}
+
+pub fn save_settings(state: &State) -> Task<Message> {
+    let future = async_save_settings(state.get_settings().clone());
+    let locale = state.get_locale();
+    Task::perform(future, move |result| match result {
+        Ok(_) => Message::NoThingToDo,
+        Err(err) => report(locale, Operation::SaveSettings, None, None, Err(err)),
+    })
+}
+
+pub fn pick_default_output_dir(state: &State) -> Task<Message> {
+    let settings = state.get_settings().clone();
+    let future = async_pick_output_dir(state.tr("dialog-pick-output-dir"), settings.output_dir.clone());
+    let locale = state.get_locale();
+    let f = move |result: Result<PathBuf>| match result {
+        Ok(output_dir) => Message::SaveSettings(Settings { output_dir: Some(output_dir), ..settings.clone() }),
+        Err(err) if err.is::<Cancelled>() => Message::NoThingToDo,
+        Err(err) => report(locale, Operation::SaveSettings, None, None, Err(err)),
     };
     Task::perform(future, f)
 }
 
-pub fn encrypt(state: &mut State) -> Task<Message> {
-    match state.get_public_key() {
+// Every edit to a name or email saves the book, so the saves are queued
+// behind each other and an older snapshot can never be written last.
+pub fn save_contacts(state: &mut State) -> Task<Message> {
+    if !state.start_contacts_save() {
+        return Task::none();
+    }
+    let future = async_save_contacts(state.get_contacts().to_vec());
+    let locale = state.get_locale();
+    Task::perform(future, move |result| Message::ContactsSaved(result.map_err(|err| tr_error(locale, &err))))
+}
+
+pub fn finish_contacts_save(state: &mut State, result: Result<(), String>) -> Task<Message> {
+    let notification = match result {
+        Ok(_) => Task::none(),
+        Err(err) => {
+            let report = Report { operation: Operation::SaveContacts, input: None, fingerprint: None, outcome: Outcome::Failed(err) };
+            dismiss_toast_later(state.notify(report))
+        }
+    };
+    match state.finish_contacts_save() {
+        true => Task::batch([notification, save_contacts(state)]),
+        false => notification,
+    }
+}
+
+pub fn pick_output_dir(state: &State) -> Task<Message> {
+    if !state.has_pending_jobs() || state.is_running_jobs() {
+        return Task::none();
+    }
+    let operation = match state.get_view_state() {
+        ViewState::Decrypting => Operation::Decrypt,
+        _ => Operation::Encrypt,
+    };
+    let locale = state.get_locale();
+    let f = move |result: Result<PathBuf>| match result {
+        Ok(output_dir) => Message::RunJobs(output_dir),
+        Err(err) => report(locale, operation, None, None, Err(err)),
+    };
+    match state.get_settings().output_dir.clone() {
+        Some(output_dir) => Task::done(Message::RunJobs(output_dir)),
+        None => Task::perform(async_pick_output_dir(state.tr("dialog-pick-output-dir"), None), f),
+    }
+}
+
+pub fn run_pending_jobs(state: &mut State) -> Task<Message> {
+    match (state.get_view_state(), state.get_job_output_dir()) {
+        (ViewState::Encrypting, Some(output_dir)) => encrypt(state, output_dir),
+        (ViewState::Decrypting, Some(output_dir)) => decrypt(state, output_dir),
+        _ => Task::none(),
+    }
+}
+
+fn encrypt(state: &mut State, output_dir: PathBuf) -> Task<Message> {
+    match state.get_encryption_key() {
         Some(public_key) => {
-            let future = async_encrypt(public_key, state.get_file_path().unwrap());
-            let f = |encrypt_result| {
-                match encrypt_result {
-                    Ok(data) => Message::SaveEncryptResult(Some(data)),
-                    _ => {
-                        Message::SaveEncryptResult(Some("Failed! File too big or bit too low".as_bytes().to_vec()))
-                    },
+            let encoding = state.get_output_encoding();
+            let options = state.get_settings().seal_options();
+            let fingerprint = fingerprint(&public_key);
+            let verification_key = state.is_shredding_originals().then(|| state.get_verification_key()).flatten();
+            let locale = state.get_locale();
+            let f = move |(index, path, overwrite): (usize, PathBuf, Overwrite)| {
+                let future = async_encrypt_file_to(public_key.clone(), options, path.clone(), output_dir.clone(), encoding, overwrite);
+                let verification_key = verification_key.clone();
+                async move {
+                    let result = match (future.await, verification_key) {
+                        (Ok(output_path), Some(private_key)) => match async_shred_verified(private_key, path, output_path.clone()).await {
+                            Ok(true) => Ok(output_path),
+                            Ok(false) => Err(Error::new(OriginalKept(output_path))),
+                            Err(err) => {
+                                let args = [("path", output_path.display().to_string().into()), ("error", tr_error(locale, &err).into())];
+                                Err(Error::msg(tr_args(locale, "shred-failed", &args)))
+                            }
+                        },
+                        (result, _) => result,
+                    };
+                    (index, result)
                 }
             };
-            Task::perform(future, f)
-        }
-        None => {
-            state.set_encrypt_result(None);
-            Task::none()
+            run_jobs(locale, state.start_jobs(fingerprint), f)
         }
+        None => Task::none(),
     }
 }
 
-pub fn decrypt(state: &mut State) -> Task<Message> {
+// An encryption job whose original couldn't be shredded, such as a
+// directory. The output is fine, so the job finishes with a warning.
+#[derive(Debug)]
+struct OriginalKept(PathBuf);
+
+impl Display for OriginalKept {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Encrypted to {}, the original was kept!", self.0.display())
+    }
+}
+
+impl std::error::Error for OriginalKept {}
+
+fn decrypt(state: &mut State, output_dir: PathBuf) -> Task<Message> {
     match state.get_private_key() {
         Some(private_key) => {
-            let future = async_decrypt(private_key, state.get_file_path().unwrap());
-            let f = |decrypt_result| {
-                match decrypt_result {
-                    Ok(data) => Message::SaveDecryptResult(Some(data)),
-                    Err(_) => {
-                        Message::SaveDecryptResult(Some("Failed! File too big or bit too low".as_bytes().to_vec()))
-                    },
-                }
+            let fingerprint = fingerprint(&private_key.to_public_key());
+            let f = move |(index, path, overwrite)| {
+                let future = async_decrypt_file_to(private_key.clone(), path, output_dir.clone(), overwrite);
+                async move { (index, future.await) }
             };
+            run_jobs(state.get_locale(), state.start_jobs(fingerprint), f)
+        }
+        None => Task::none(),
+    }
+}
+
+fn run_jobs<F, Fut>(locale: Locale, jobs: Vec<(usize, PathBuf, Overwrite)>, f: F) -> Task<Message>
+where
+    F: FnMut((usize, PathBuf, Overwrite)) -> Fut + Send + 'static,
+    Fut: Future<Output = (usize, Result<PathBuf>)> + Send + 'static,
+{
+    let stream = stream::iter(jobs).map(f).buffer_unordered(MAX_CONCURRENT_JOBS);
+    Task::run(stream, move |(index, result)| match result {
+        Err(err) if err.is::<AlreadyExists>() => {
+            let path = err.downcast::<AlreadyExists>().map(|exists| exists.0).unwrap_or_default();
+            Message::Conflict(Conflict { source: ConflictSource::Job(index), path })
+        }
+        Err(err) if err.is::<OriginalKept>() => {
+            let output_path = err.downcast::<OriginalKept>().map(|kept| kept.0).unwrap_or_default();
+            Message::FinishJob(index, Ok((output_path, Some(tr(locale, "shred-original-kept")))))
+        }
+        result => Message::FinishJob(index, result.map(|output_path| (output_path, None)).map_err(|err| tr_error(locale, &err))),
+    })
+}
+
+pub fn resolve_conflict(state: &mut State, choice: ConflictChoice) -> Task<Message> {
+    let conflicts = state.take_conflicts(choice);
+    apply_conflict_choice(state, conflicts, choice)
+}
+
+pub fn apply_conflict_choice(state: &mut State, conflicts: Vec<Conflict>, choice: ConflictChoice) -> Task<Message> {
+    let mut tasks = Vec::new();
+    let mut retry = false;
+    for conflict in conflicts {
+        match (conflict.source, choice.overwrite()) {
+            (ConflictSource::Job(index), Some(overwrite)) => {
+                state.retry_job(index, overwrite);
+                retry = true;
+            }
+            (ConflictSource::Job(index), None) => {
+                let skipped = state.tr_args("job-skipped", &[("path", conflict.path.display().to_string().into())]);
+                tasks.push(Task::done(Message::FinishJob(index, Err(skipped))));
+            }
+            (ConflictSource::KeyFile(key_file), Some(overwrite)) => tasks.push(write_pem_file(state, key_file, conflict.path, overwrite)),
+            (ConflictSource::KeyFile(key_file), None) => {
+                let (operation, _, fingerprint) = key_file_details(state, key_file);
+                tasks.push(Task::done(report(state.get_locale(), operation, None, fingerprint, Err(Error::new(Cancelled)))));
+            }
+        }
+    }
+    if retry {
+        tasks.push(run_pending_jobs(state));
+    }
+    Task::batch(tasks)
+}
+
+pub fn report(locale: Locale, operation: Operation, input: Option<PathBuf>, fingerprint: Option<String>, result: Result<Option<PathBuf>>) -> Message {
+    let outcome = match result {
+        Ok(output_path) => Outcome::Succeeded(output_path),
+        Err(err) if err.is::<Cancelled>() => Outcome::Cancelled,
+        Err(err) => Outcome::Failed(tr_error(locale, &err)),
+    };
+    Message::Report(Report { operation, input, fingerprint, outcome })
+}
+
+pub fn dismiss_toast_later(id: u64) -> Task<Message> {
+    Task::perform(tokio::time::sleep(TOAST_TIMEOUT), move |_| Message::DismissToast(id))
+}
+
+pub fn encrypt_text(state: &State) -> Task<Message> {
+    match state.get_encryption_key() {
+        Some(public_key) => {
+            let fingerprint = fingerprint(&public_key);
+            let future = async_encrypt_text(public_key, state.get_settings().seal_options(), state.get_text_input_content().text());
+            let locale = state.get_locale();
+            let f = move |result: Result<String>| Message::FinishText(Operation::EncryptText, Some(fingerprint.clone()), result.map_err(|err| tr_error(locale, &err)));
             Task::perform(future, f)
         }
-        None => {
-            state.set_decrypt_result(None);
-            Task::none()
+        None => Task::none(),
+    }
+}
+
+pub fn decrypt_text(state: &State) -> Task<Message> {
+    match state.get_private_key() {
+        Some(private_key) => {
+            let fingerprint = fingerprint(&private_key.to_public_key());
+            let future = async_decrypt_text(private_key, state.get_text_input_content().text());
+            let locale = state.get_locale();
+            let f = move |result: Result<String>| Message::FinishText(Operation::DecryptText, Some(fingerprint.clone()), result.map_err(|err| tr_error(locale, &err)));
+            Task::perform(future, f)
         }
+        None => Task::none(),
     }
 }
 
-pub fn save_encrypt_result(file_name: String, data: Option<Vec<u8>>) -> Task<Message> {
-    let future = async_save_encrypted_file(file_name, data);
-    let f = |_| Message::NoThingToDo;
+pub fn copy_to_clipboard(text: String) -> Task<Message> {
+    clipboard::write(text)
+}
+
+pub fn copy_secret_to_clipboard(text: String) -> Task<Message> {
+    let secret = text.clone();
+    let expire = Task::perform(tokio::time::sleep(CLIPBOARD_SECRET_TIMEOUT), move |_| Message::ExpireClipboard(secret.clone()));
+    clipboard::write(text).chain(expire)
+}
+
+pub fn expire_clipboard(secret: String) -> Task<Message> {
+    clipboard::read().then(move |content| match content.as_deref() == Some(secret.as_str()) {
+        true => clipboard::write(String::new()),
+        false => Task::none(),
+    })
+}
+
+pub fn paste_text_input() -> Task<Message> {
+    clipboard::read().map(Message::SetTextInput)
+}
+
+pub fn paste_public_key() -> Task<Message> {
+    clipboard::read().map(Message::SetPublicKeyText)
+}
+
+pub fn decrypt_from_clipboard() -> Task<Message> {
+    clipboard::read().map(Message::DecryptText)
+}
+
+pub fn pick_files(state: &State) -> Task<Message> {
+    let future = async_pick_files(state.tr("dialog-pick-files"));
+    let f = |file_handles: Result<Vec<FileHandle>>| {
+        match file_handles {
+            Ok(file_handles) => Message::AddJobs(file_handles.iter().map(|file_handle| file_handle.path().to_path_buf()).collect()),
+            Err(_) => Message::NoThingToDo,
+        }
+    };
     Task::perform(future, f)
 }
 
-pub fn save_decrypt_result(file_name: String, data: Option<Vec<u8>>) -> Task<Message> {
-    let future = async_save_decrypted_file(file_name.replace(".encrypted", ""), data);
-    let f = |_| Message::NoThingToDo;
+pub fn pick_inspect_file(state: &State) -> Task<Message> {
+    let f = |result: Result<PathBuf>| match result {
+        Ok(path) => Message::InspectFile(path),
+        Err(_) => Message::NoThingToDo,
+    };
+    Task::perform(async_pick_file(state.tr("dialog-pick-file")), f)
+}
+
+pub fn inspect_file(state: &State, path: PathBuf) -> Task<Message> {
+    let future = async_inspect_file(path.clone());
+    let locale = state.get_locale();
+    let f = move |result: Result<Inspection>| Message::SetInspection(path.clone(), result.map_err(|err| tr_error(locale, &err)));
     Task::perform(future, f)
 }
 
-pub fn pick_file() -> Task<Message> {
-    let future = async_pick_file();
-    let f = |file_handle: Result<FileHandle>| {
-        match file_handle {
-            Ok(file_handle) => Message::SetFilePath(file_handle.path().to_path_buf()),
-            _ => Message::NoThingToDo,
-        }
+// Anything that isn't a container keeps the view's usual drop behaviour.
+pub fn inspect_dropped_file(path: PathBuf) -> Task<Message> {
+    let future = async_inspect_file(path.clone());
+    let f = move |result: Result<Inspection>| match result {
+        Ok(inspection) => Message::DropContainer(path.clone(), inspection),
+        Err(_) => Message::DropFile(path.clone()),
     };
     Task::perform(future, f)
-}
\ No newline at end of file
+}
+
+pub fn drop_container(state: &mut State, path: PathBuf, inspection: Inspection) -> Task<Message> {
+    if state.is_running_jobs() && state.get_view_state() != ViewState::Decrypting {
+        return Task::done(Message::DropFile(path));
+    }
+    if state.has_work_outside_decryption() {
+        return refuse_drop(state, path);
+    }
+    let fingerprints: Vec<String> = inspection.recipients.iter().map(|(fingerprint, _)| fingerprint.clone()).collect();
+    if fingerprints.iter().any(|fingerprint| state.find_key_owner(fingerprint) == Some(KeyOwner::PrivateKey)) {
+        state.queue_for_decryption(path);
+        return Task::none();
+    }
+    let future = async_find_stored_private_key(fingerprints, state.get_passphrase());
+    let locale = state.get_locale();
+    let f = move |result| match result {
+        Ok(Some((key_path, private_key))) => Message::UseStoredKey(path.clone(), key_path, private_key),
+        Ok(None) => Message::MissingKey(path.clone(), inspection.clone()),
+        Err(err) => report(locale, Operation::Decrypt, Some(path.clone()), None, Err(err)),
+    };
+    Task::perform(future, f)
+}
+
+// A stored key is loaded like one opened by hand, including the warning
+// about a key file others can read.
+pub fn use_stored_key(state: &mut State, path: PathBuf, key_path: PathBuf, private_key: RsaPrivateKey) -> Task<Message> {
+    if state.has_work_outside_decryption() {
+        return refuse_drop(state, path);
+    }
+    state.fill_private_key(private_key);
+    state.queue_for_decryption(path);
+    Task::batch([priv_key_to_pub_key_and_fill(state), check_key_file(key_path)])
+}
+
+fn refuse_drop(state: &mut State, path: PathBuf) -> Task<Message> {
+    let report = Report {
+        operation: Operation::Decrypt,
+        input: Some(path),
+        fingerprint: None,
+        outcome: Outcome::Failed(state.tr("drop-view-busy")),
+    };
+    let id = state.notify(report);
+    dismiss_toast_later(id)
+}
+
+pub fn missing_key(state: &mut State, path: PathBuf, inspection: Inspection) -> Task<Message> {
+    let fingerprints: Vec<&str> = inspection.recipients.iter().map(|(fingerprint, _)| fingerprint.as_str()).collect();
+    let error = state.tr_args("drop-missing-key", &[("count", fingerprints.len().into()), ("fingerprints", fingerprints.join(", ").into())]);
+    let report = Report {
+        operation: Operation::Decrypt,
+        input: Some(path.clone()),
+        fingerprint: fingerprints.first().map(|fingerprint| fingerprint.to_string()),
+        outcome: Outcome::Failed(error),
+    };
+    state.set_inspection(Some((path, Ok(inspection))));
+    let id = state.notify(report);
+    dismiss_toast_later(id)
+}
+
+pub fn run_benchmark(state: &mut State) -> Task<Message> {
+    state.set_benchmark(None);
+    let locale = state.get_locale();
+    let f = move |result: Result<Vec<(AeadAlgorithm, f64)>>| Message::SetBenchmark(result.map_err(|err| tr_error(locale, &err)));
+    Task::perform(async_benchmark_aead(), f)
+}
+
+pub fn save_recipients(state: &mut State) -> Task<Message> {
+    let (Some(private_key), Some((path, Ok(inspection))), Some(selection)) = (state.get_rewrap_key(), state.get_inspection(), state.get_recipients_editor()) else {
+        return Task::none();
+    };
+    let keep: Vec<String> = inspection
+        .recipients
+        .iter()
+        .map(|(recipient, _)| recipient.clone())
+        .filter(|recipient| selection.contains(recipient))
+        .collect();
+    let add: Vec<RsaPublicKey> = state
+        .get_recipient_candidates()
+        .into_iter()
+        .filter(|(candidate, _)| selection.contains(candidate))
+        .map(|(_, public_key)| public_key)
+        .collect();
+    let path = path.clone();
+    state.set_saving_recipients(true);
+    let future = async_rewrap_file(private_key, path.clone(), keep, add, state.get_settings().key_transport);
+    let locale = state.get_locale();
+    let f = move |result: Result<PathBuf>| Message::FinishRecipients(path.clone(), result.map_err(|err| tr_error(locale, &err)));
+    Task::perform(future, f)
+}
+
+pub fn finish_recipients(state: &mut State, path: PathBuf, result: Result<PathBuf, String>) -> Task<Message> {
+    state.set_saving_recipients(false);
+    let outcome = match &result {
+        Ok(output_path) => Outcome::Replaced(output_path.clone()),
+        Err(err) => Outcome::Failed(err.clone()),
+    };
+    let id = state.notify(Report { operation: Operation::EditRecipients, input: Some(path.clone()), fingerprint: None, outcome });
+    match result {
+        Ok(_) => Task::batch([dismiss_toast_later(id), inspect_file(state, path)]),
+        Err(_) => dismiss_toast_later(id),
+    }
+}
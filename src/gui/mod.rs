@@ -4,6 +4,10 @@ mod update;
 mod components;
 mod view;
 mod subscription;
+mod keymap;
+mod theme;
+mod i18n;
 mod lib;
 
-pub use lib::run;
\ No newline at end of file
+pub use lib::run;
+pub(crate) use i18n::{tr, tr_args, tr_error, Locale};
\ No newline at end of file
@@ -1,75 +1,552 @@
 use std::path::PathBuf;
-use iced::widget::text_editor::{self, Action, Content, Edit};
+use chrono::{DateTime, Local};
+use fluent_bundle::FluentValue;
+use iced::{task::Handle, widget::text_editor::{self, Content}};
 use rsa::{pkcs1::{EncodeRsaPrivateKey, EncodeRsaPublicKey}, RsaPrivateKey, RsaPublicKey};
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+use crate::core::{fingerprint, import_contact, AeadAlgorithm, Contact, Inspection, KeyFormat, OutputEncoding, Overwrite, Settings};
+
+use super::{i18n::{tr, tr_args, Locale}, keymap::Keymap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ViewState {
+    #[default]
     Setting,
     Encrypting,
     Decrypting,
+    Preferences,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JobStatus {
+    Pending,
+    Running,
+    // The output path and an optional warning.
+    Done(PathBuf, Option<String>),
+    Failed(String),
+    Conflict(PathBuf),
+}
+
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub path: PathBuf,
+    pub status: JobStatus,
+    pub fingerprint: Option<String>,
+    pub overwrite: Overwrite,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyFile {
+    Private,
+    Public,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSource {
+    Job(usize),
+    KeyFile(KeyFile),
+}
+
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub source: ConflictSource,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictChoice {
+    Replace,
+    KeepBoth,
+    Skip,
+}
+
+impl ConflictChoice {
+    pub fn overwrite(self) -> Option<Overwrite> {
+        match self {
+            ConflictChoice::Replace => Some(Overwrite::Replace),
+            ConflictChoice::KeepBoth => Some(Overwrite::Rename),
+            ConflictChoice::Skip => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    GenerateKey,
+    SavePrivateKey,
+    SavePublicKey,
+    ImportKey,
+    Encrypt,
+    Decrypt,
+    EncryptText,
+    DecryptText,
+    LoadContacts,
+    SaveContacts,
+    LoadKeymap,
+    LoadSettings,
+    SaveSettings,
+    RestrictKeyFile,
+    EditRecipients,
 }
 
-impl Default for ViewState {
-    fn default() -> Self {
-        Self::Setting
+impl Operation {
+    pub fn label_id(self) -> &'static str {
+        match self {
+            Operation::GenerateKey => "op-generate-key",
+            Operation::SavePrivateKey => "op-save-private-key",
+            Operation::SavePublicKey => "op-save-public-key",
+            Operation::ImportKey => "op-import-key",
+            Operation::Encrypt => "op-encrypt",
+            Operation::Decrypt => "op-decrypt",
+            Operation::EncryptText => "op-encrypt-text",
+            Operation::DecryptText => "op-decrypt-text",
+            Operation::LoadContacts => "op-load-contacts",
+            Operation::SaveContacts => "op-save-contacts",
+            Operation::LoadKeymap => "op-load-keymap",
+            Operation::LoadSettings => "op-load-settings",
+            Operation::SaveSettings => "op-save-settings",
+            Operation::RestrictKeyFile => "op-restrict-key-file",
+            Operation::EditRecipients => "op-edit-recipients",
+        }
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Succeeded(Option<PathBuf>),
+    // An existing file was rewritten in place.
+    Replaced(PathBuf),
+    Cancelled,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub operation: Operation,
+    pub input: Option<PathBuf>,
+    pub fingerprint: Option<String>,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub timestamp: DateTime<Local>,
+    pub report: Report,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Success,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub id: u64,
+    pub kind: ToastKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyOwner {
+    PrivateKey,
+    PublicKey,
+    Contact(String),
+}
+
 #[derive(Default)]
 pub struct State {
     view_state: ViewState,
     priv_key_text_content: text_editor::Content,
     pub_key_text_content: text_editor::Content,
+    priv_key_generation: u64,
+    pub_key_generation: u64,
+    priv_key_parse: Option<Handle>,
+    pub_key_parse: Option<Handle>,
+    passphrase: String,
     private_key: Option<RsaPrivateKey>,
+    private_key_format: Option<KeyFormat>,
+    private_key_diagnostic: Option<String>,
+    exposed_key_file: Option<(PathBuf, u32)>,
     public_key: Option<RsaPublicKey>,
-    file_path: Option<PathBuf>,
-    encrypt_result: Option<Vec<u8>>,
-    decrypt_result: Option<Vec<u8>>,
+    public_key_format: Option<KeyFormat>,
+    public_key_diagnostic: Option<String>,
+    jobs: Vec<Job>,
+    output_encoding: OutputEncoding,
+    text_input_content: text_editor::Content,
+    text_output_content: text_editor::Content,
+    stashed_text: Vec<(ViewState, text_editor::Content, text_editor::Content)>,
+    contacts: Vec<Contact>,
+    saving_contacts: bool,
+    contacts_changed: bool,
+    recipient: Option<String>,
+    keymap: Keymap,
+    show_cheat_sheet: bool,
+    settings: Settings,
+    locale: Locale,
+    history: Vec<HistoryEntry>,
+    show_history: bool,
+    toasts: Vec<Toast>,
+    next_toast_id: u64,
+    inspection: Option<(PathBuf, Result<Inspection, String>)>,
+    recipients_editor: Option<Vec<String>>,
+    saving_recipients: bool,
+    job_output_dir: Option<PathBuf>,
+    conflicts: Vec<Conflict>,
+    apply_to_all_conflicts: bool,
+    conflict_policy: Option<ConflictChoice>,
+    shred_originals: bool,
+    confirming_shred: bool,
+    show_benchmark: bool,
+    benchmark: Option<Result<Vec<(AeadAlgorithm, f64)>, String>>,
 }
 
 impl State {
-    pub fn set_encrypt_result(&mut self, encrypt_result: Option<Vec<u8>>) {
-        self.encrypt_result = encrypt_result;
+    pub fn with_settings(settings: Settings) -> Self {
+        Self {
+            output_encoding: settings.output_encoding,
+            locale: Locale::resolve(settings.language),
+            settings,
+            ..Self::default()
+        }
+    }
+
+    pub fn get_settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn set_settings(&mut self, settings: Settings) {
+        if settings.output_encoding != self.settings.output_encoding {
+            self.output_encoding = settings.output_encoding;
+        }
+        self.locale = Locale::resolve(settings.language);
+        self.settings = settings;
+    }
+
+    pub fn get_locale(&self) -> Locale {
+        self.locale
+    }
+
+    pub fn tr(&self, id: &str) -> String {
+        tr(self.locale, id)
+    }
+
+    pub fn tr_args(&self, id: &str, args: &[(&str, FluentValue<'_>)]) -> String {
+        tr_args(self.locale, id, args)
+    }
+
+    pub fn get_jobs(&self) -> &[Job] {
+        &self.jobs
+    }
+
+    pub fn add_job(&mut self, path: PathBuf) {
+        if self.jobs.iter().all(|job| job.path != path) {
+            self.jobs.push(Job { path, status: JobStatus::Pending, fingerprint: None, overwrite: Overwrite::Ask });
+        }
+    }
+
+    // Finished jobs of another view are dropped, as when switching views by
+    // hand. Pending jobs and text are checked with `has_work_outside_decryption`
+    // first, so nothing the user still wanted is lost.
+    pub fn queue_for_decryption(&mut self, path: PathBuf) {
+        if self.view_state != ViewState::Decrypting {
+            self.clear_jobs();
+            self.set_view_state(ViewState::Decrypting);
+        }
+        self.add_job(path);
+    }
+
+    pub fn has_work_outside_decryption(&self) -> bool {
+        self.view_state != ViewState::Decrypting && (self.has_pending_jobs() || !self.text_input_content.text().trim().is_empty())
+    }
+
+    pub fn clear_jobs(&mut self) {
+        if !self.is_running_jobs() {
+            self.jobs.clear();
+            self.conflicts.retain(|conflict| !matches!(conflict.source, ConflictSource::Job(_)));
+        }
+    }
+
+    pub fn has_pending_jobs(&self) -> bool {
+        self.jobs.iter().any(|job| job.status == JobStatus::Pending)
+    }
+
+    pub fn is_running_jobs(&self) -> bool {
+        self.jobs.iter().any(|job| job.status == JobStatus::Running)
+    }
+
+    pub fn start_jobs(&mut self, fingerprint: String) -> Vec<(usize, PathBuf, Overwrite)> {
+        self.jobs
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, job)| job.status == JobStatus::Pending)
+            .map(|(index, job)| {
+                job.status = JobStatus::Running;
+                job.fingerprint = Some(fingerprint.clone());
+                (index, job.path.clone(), job.overwrite)
+            })
+            .collect()
+    }
+
+    pub fn get_job_output_dir(&self) -> Option<PathBuf> {
+        self.job_output_dir.clone()
+    }
+
+    // A new run starts with a fresh output directory and asks again about
+    // existing files.
+    pub fn set_job_output_dir(&mut self, output_dir: PathBuf) {
+        self.job_output_dir = Some(output_dir);
+        self.conflict_policy = None;
+    }
+
+    // Saving a key pair is a run of its own, so "apply to all" on the first
+    // file settles the second one too.
+    pub fn start_key_save(&mut self) {
+        self.conflict_policy = None;
+    }
+
+    pub fn retry_job(&mut self, index: usize, overwrite: Overwrite) {
+        if let Some(job) = self.jobs.get_mut(index) {
+            job.status = JobStatus::Pending;
+            job.overwrite = overwrite;
+        }
+    }
+
+    pub fn get_conflict(&self) -> Option<&Conflict> {
+        self.conflicts.first()
+    }
+
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    pub fn get_conflict_policy(&self) -> Option<ConflictChoice> {
+        self.conflict_policy
     }
-    pub fn set_decrypt_result(&mut self, decrypt_result: Option<Vec<u8>>) {
-        self.decrypt_result = decrypt_result;
+
+    pub fn push_conflict(&mut self, conflict: Conflict) {
+        if let ConflictSource::Job(index) = conflict.source {
+            if let Some(job) = self.jobs.get_mut(index) {
+                job.status = JobStatus::Conflict(conflict.path.clone());
+            }
+        }
+        self.conflicts.push(conflict);
+    }
+
+    pub fn is_applying_to_all_conflicts(&self) -> bool {
+        self.apply_to_all_conflicts
     }
 
-    pub fn set_file_path(&mut self, path: Option<PathBuf>) {
-        self.file_path = path;
+    pub fn set_apply_to_all_conflicts(&mut self, apply_to_all: bool) {
+        self.apply_to_all_conflicts = apply_to_all;
     }
 
-    pub fn get_file_path(&self) -> Option<PathBuf> {
-        self.file_path.clone()
+    // With "apply to all" checked the choice also settles every waiting
+    // conflict and the ones still to come from this run.
+    pub fn take_conflicts(&mut self, choice: ConflictChoice) -> Vec<Conflict> {
+        match self.apply_to_all_conflicts {
+            true => {
+                self.conflict_policy = Some(choice);
+                self.apply_to_all_conflicts = false;
+                self.conflicts.drain(..).collect()
+            }
+            false if self.conflicts.is_empty() => Vec::new(),
+            false => vec![self.conflicts.remove(0)],
+        }
     }
 
-    pub fn get_file_name(&self) -> String {
-        match self.get_file_path() {
-            Some(path) => path.file_name().map(|os_str| os_str.to_str().unwrap_or("")).unwrap_or("").to_string(),
-            None => String::default()
+    pub fn finish_job(&mut self, index: usize, result: Result<(PathBuf, Option<String>), String>) {
+        let operation = match self.view_state {
+            ViewState::Decrypting => Operation::Decrypt,
+            _ => Operation::Encrypt,
+        };
+        if let Some(job) = self.jobs.get_mut(index) {
+            let outcome = match &result {
+                Ok((output_path, _)) => Outcome::Succeeded(Some(output_path.clone())),
+                Err(err) => Outcome::Failed(err.clone()),
+            };
+            job.status = match result {
+                Ok((output_path, warning)) => JobStatus::Done(output_path, warning),
+                Err(err) => JobStatus::Failed(err),
+            };
+            let report = Report { operation, input: Some(job.path.clone()), fingerprint: job.fingerprint.clone(), outcome };
+            self.history.push(HistoryEntry { timestamp: Local::now(), report });
         }
     }
 
+    pub fn get_history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    pub fn is_showing_history(&self) -> bool {
+        self.show_history
+    }
+
+    pub fn toggle_history(&mut self) {
+        self.show_history = !self.show_history;
+    }
+
+    pub fn get_toasts(&self) -> &[Toast] {
+        &self.toasts
+    }
+
+    pub fn push_toast(&mut self, kind: ToastKind, text: String) -> u64 {
+        let id = self.next_toast_id;
+        self.next_toast_id += 1;
+        self.toasts.push(Toast { id, kind, text });
+        id
+    }
+
+    pub fn dismiss_toast(&mut self, id: u64) {
+        self.toasts.retain(|toast| toast.id != id);
+    }
+
+    pub fn notify(&mut self, report: Report) -> u64 {
+        let operation = self.tr(report.operation.label_id());
+        let (kind, text) = match &report.outcome {
+            Outcome::Succeeded(Some(output_path)) => (ToastKind::Success, self.tr_args("toast-saved", &[("operation", operation.into()), ("path", output_path.display().to_string().into())])),
+            Outcome::Succeeded(None) => (ToastKind::Success, self.tr_args("toast-succeeded", &[("operation", operation.into())])),
+            Outcome::Replaced(path) => (ToastKind::Success, self.tr_args("toast-replaced", &[("operation", operation.into()), ("path", path.display().to_string().into())])),
+            Outcome::Cancelled => (ToastKind::Warning, self.tr_args("toast-cancelled", &[("operation", operation.into())])),
+            Outcome::Failed(err) => (ToastKind::Error, self.tr_args("toast-failed", &[("operation", operation.into()), ("error", err.as_str().into())])),
+        };
+        self.history.push(HistoryEntry { timestamp: Local::now(), report });
+        self.push_toast(kind, text)
+    }
+
+    pub fn get_output_encoding(&self) -> OutputEncoding {
+        self.output_encoding
+    }
+
+    pub fn set_output_encoding(&mut self, output_encoding: OutputEncoding) {
+        self.output_encoding = output_encoding;
+    }
+
+    pub fn get_text_input_content(&self) -> &text_editor::Content {
+        &(self.text_input_content)
+    }
+
+    pub fn perform_text_input_content(&mut self, action: text_editor::Action) {
+        self.text_input_content.perform(action);
+    }
+
+    pub fn set_text_input(&mut self, text: &str) {
+        self.text_input_content = Content::with_text(text);
+    }
+
+    pub fn get_text_output_content(&self) -> &text_editor::Content {
+        &(self.text_output_content)
+    }
+
+    pub fn perform_text_output_content(&mut self, action: text_editor::Action) {
+        if !action.is_edit() {
+            self.text_output_content.perform(action);
+        }
+    }
+
+    pub fn set_text_output(&mut self, text: &str) {
+        self.text_output_content = Content::with_text(text);
+    }
+
+    // Each view keeps its own text panel, so switching away, e.g. with Tab
+    // while typing, never throws the text or its result away.
+    fn set_view_state(&mut self, view_state: ViewState) {
+        if view_state == self.view_state {
+            return;
+        }
+        let (input, output) = match self.stashed_text.iter().position(|(other, _, _)| *other == view_state) {
+            Some(index) => {
+                let (_, input, output) = self.stashed_text.swap_remove(index);
+                (input, output)
+            }
+            None => (Content::new(), Content::new()),
+        };
+        let input = std::mem::replace(&mut self.text_input_content, input);
+        let output = std::mem::replace(&mut self.text_output_content, output);
+        self.stashed_text.push((self.view_state, input, output));
+        self.view_state = view_state;
+    }
+
     pub fn switch_view_state(&mut self) {
-        if self.get_view_state() == ViewState::Setting && self.public_key.is_some() {
-            self.view_state = ViewState::Encrypting;
+        if self.get_view_state() == ViewState::Setting && (self.public_key.is_some() || !self.contacts.is_empty()) {
+            self.set_view_state(ViewState::Encrypting);
             return;
         }
         if self.get_view_state() == ViewState::Encrypting && self.private_key.is_some() {
-            self.view_state = ViewState::Decrypting;
+            self.set_view_state(ViewState::Decrypting);
             return;
         }
-        if self.get_view_state() == ViewState::Setting && self.public_key.is_none() && self.private_key.is_some() {
-            self.view_state = ViewState::Decrypting;
+        if self.get_view_state() == ViewState::Setting && self.public_key.is_none() && self.contacts.is_empty() && self.private_key.is_some() {
+            self.set_view_state(ViewState::Decrypting);
             return;
         }
-        self.view_state = ViewState::Setting;
+        self.set_view_state(ViewState::Setting);
     }
+
+    pub fn can_select_view_state(&self, view_state: ViewState) -> bool {
+        match view_state {
+            ViewState::Setting => true,
+            ViewState::Encrypting => self.public_key.is_some() || !self.contacts.is_empty(),
+            ViewState::Decrypting => self.private_key.is_some(),
+            ViewState::Preferences => true,
+        }
+    }
+
+    pub fn select_view_state(&mut self, view_state: ViewState) {
+        if self.can_select_view_state(view_state) {
+            self.set_view_state(view_state);
+        }
+    }
+
     pub fn get_view_state(&self) -> ViewState {
         self.view_state
     }
-    pub fn set_private_key(&mut self, private_key: Option<RsaPrivateKey>) {
+
+    pub fn set_private_key(&mut self, private_key: Option<RsaPrivateKey>, format: Option<KeyFormat>) {
         self.private_key = private_key;
+        self.private_key_format = format;
+        self.private_key_diagnostic = None;
+        self.exposed_key_file = None;
+    }
+
+    pub fn get_private_key_diagnostic(&self) -> Option<&str> {
+        self.private_key_diagnostic.as_deref()
+    }
+
+    pub fn set_private_key_diagnostic(&mut self, diagnostic: Option<String>) {
+        self.private_key_diagnostic = diagnostic;
+    }
+
+    pub fn get_exposed_key_file(&self) -> Option<&(PathBuf, u32)> {
+        self.exposed_key_file.as_ref()
+    }
+
+    pub fn set_exposed_key_file(&mut self, exposed_key_file: Option<(PathBuf, u32)>) {
+        self.exposed_key_file = exposed_key_file;
+    }
+
+    pub fn get_private_key_format(&self) -> Option<KeyFormat> {
+        self.private_key_format
+    }
+
+    pub fn get_passphrase(&self) -> Option<String> {
+        match self.passphrase.is_empty() {
+            true => None,
+            false => Some(self.passphrase.clone()),
+        }
+    }
+
+    pub fn get_passphrase_text(&self) -> &str {
+        &self.passphrase
+    }
+
+    pub fn set_passphrase(&mut self, passphrase: String) {
+        self.passphrase = passphrase;
     }
 
     pub fn get_private_key(&self) -> Option<RsaPrivateKey> {
@@ -84,20 +561,46 @@ impl State {
         self.priv_key_text_content.perform(action);
     }
 
-    pub fn clean_priv_key_text_content(&mut self) {
-        self.priv_key_text_content = Content::new();
+    pub fn fill_private_key(&mut self, private_key: RsaPrivateKey) {
+        self.next_priv_key_generation();
+        self.priv_key_text_content = Content::with_text(&private_key.to_pkcs1_pem(rsa::pkcs8::LineEnding::LF).unwrap());
+        self.private_key = Some(private_key);
+        self.private_key_format = Some(KeyFormat::Pkcs1Pem);
+        self.private_key_diagnostic = None;
     }
 
-    pub fn fill_private_key(&mut self, private_key: RsaPrivateKey) {
-        self.clean_priv_key_text_content();
-        for c in format!("{}", private_key.to_pkcs1_pem(rsa::pkcs8::LineEnding::LF).unwrap().to_string()).chars() {
-            self.perform_priv_key_text_content(Action::Edit(Edit::Insert(c)));
+    pub fn get_priv_key_generation(&self) -> u64 {
+        self.priv_key_generation
+    }
+
+    pub fn next_priv_key_generation(&mut self) -> u64 {
+        if let Some(handle) = self.priv_key_parse.take() {
+            handle.abort();
         }
-        self.private_key = Some(private_key);
+        self.priv_key_generation += 1;
+        self.priv_key_generation
+    }
+
+    pub fn set_priv_key_parse(&mut self, handle: Handle) {
+        self.priv_key_parse = Some(handle);
     }
 
-    pub fn set_public_key(&mut self, public_key: Option<RsaPublicKey>) {
+    pub fn set_public_key(&mut self, public_key: Option<RsaPublicKey>, format: Option<KeyFormat>) {
         self.public_key = public_key;
+        self.public_key_format = format;
+        self.public_key_diagnostic = None;
+    }
+
+    pub fn get_public_key_diagnostic(&self) -> Option<&str> {
+        self.public_key_diagnostic.as_deref()
+    }
+
+    pub fn set_public_key_diagnostic(&mut self, diagnostic: Option<String>) {
+        self.public_key_diagnostic = diagnostic;
+    }
+
+    pub fn get_public_key_format(&self) -> Option<KeyFormat> {
+        self.public_key_format
     }
 
     pub fn get_public_key(&self) -> Option<RsaPublicKey> {
@@ -112,15 +615,240 @@ impl State {
         self.pub_key_text_content.perform(action);
     }
 
-    pub fn clean_pub_key_text_content(&mut self) {
-        self.pub_key_text_content = Content::new();
+    pub fn fill_public_key(&mut self, public_key: RsaPublicKey) {
+        self.next_pub_key_generation();
+        self.pub_key_text_content = Content::with_text(&public_key.to_pkcs1_pem(rsa::pkcs8::LineEnding::LF).unwrap());
+        self.public_key = Some(public_key);
+        self.public_key_format = Some(KeyFormat::Pkcs1Pem);
+        self.public_key_diagnostic = None;
+    }
+
+    pub fn set_pub_key_text(&mut self, text: &str) {
+        self.pub_key_text_content = Content::with_text(text);
     }
 
-    pub fn fill_public_key(&mut self, public_key: RsaPublicKey) {
-        self.clean_pub_key_text_content();
-        for c in format!("{}", public_key.to_pkcs1_pem(rsa::pkcs8::LineEnding::LF).unwrap()).chars() {
-            self.perform_pub_key_text_content(Action::Edit(Edit::Insert(c)));
+    pub fn get_pub_key_generation(&self) -> u64 {
+        self.pub_key_generation
+    }
+
+    pub fn next_pub_key_generation(&mut self) -> u64 {
+        if let Some(handle) = self.pub_key_parse.take() {
+            handle.abort();
         }
-        self.public_key = Some(public_key);
+        self.pub_key_generation += 1;
+        self.pub_key_generation
+    }
+
+    pub fn set_pub_key_parse(&mut self, handle: Handle) {
+        self.pub_key_parse = Some(handle);
+    }
+
+    pub fn get_encryption_key(&self) -> Option<RsaPublicKey> {
+        match self.get_recipient() {
+            Some(contact) => Some(contact.public_key.clone()),
+            None => self.get_public_key(),
+        }
+    }
+
+    // Shredding is only offered when the loaded private key can decrypt the
+    // output, since that is how each result is verified first.
+    pub fn get_verification_key(&self) -> Option<RsaPrivateKey> {
+        let private_key = self.private_key.as_ref()?;
+        let public_key = self.get_encryption_key()?;
+        (fingerprint(&private_key.to_public_key()) == fingerprint(&public_key)).then(|| private_key.clone())
+    }
+
+    pub fn is_shredding_originals(&self) -> bool {
+        self.shred_originals && self.get_verification_key().is_some()
+    }
+
+    pub fn set_shred_originals(&mut self, shred_originals: bool) {
+        self.shred_originals = shred_originals;
+        self.confirming_shred = false;
+    }
+
+    pub fn is_confirming_shred(&self) -> bool {
+        self.confirming_shred
+    }
+
+    pub fn set_confirming_shred(&mut self, confirming_shred: bool) {
+        self.confirming_shred = confirming_shred;
+    }
+
+    pub fn get_contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    pub fn set_contacts(&mut self, contacts: Vec<Contact>) {
+        self.contacts = contacts;
+    }
+
+    pub fn add_contact(&mut self, contact: Contact) {
+        import_contact(&mut self.contacts, contact);
     }
-}
\ No newline at end of file
+
+    pub fn remove_contact(&mut self, fingerprint: &str) {
+        self.contacts.retain(|contact| contact.fingerprint != fingerprint);
+        if self.recipient.as_deref() == Some(fingerprint) {
+            self.recipient = None;
+        }
+    }
+
+    // Saves run one at a time. A change made while one is running only marks
+    // the book, and `finish_contacts_save` asks for another save with the
+    // latest contents once it's done.
+    pub fn start_contacts_save(&mut self) -> bool {
+        match self.saving_contacts {
+            true => {
+                self.contacts_changed = true;
+                false
+            }
+            false => {
+                self.saving_contacts = true;
+                true
+            }
+        }
+    }
+
+    pub fn finish_contacts_save(&mut self) -> bool {
+        self.saving_contacts = false;
+        std::mem::take(&mut self.contacts_changed)
+    }
+
+    pub fn set_contact_name(&mut self, index: usize, name: String) {
+        if let Some(contact) = self.contacts.get_mut(index) {
+            contact.name = name;
+        }
+    }
+
+    pub fn set_contact_email(&mut self, index: usize, email: String) {
+        if let Some(contact) = self.contacts.get_mut(index) {
+            contact.email = email;
+        }
+    }
+
+    // The recipient is kept by fingerprint, so renaming the contact shows up
+    // in the picker and removing it falls back to the public key editor.
+    pub fn get_recipient(&self) -> Option<&Contact> {
+        let recipient = self.recipient.as_ref()?;
+        self.contacts.iter().find(|contact| contact.fingerprint == *recipient)
+    }
+
+    pub fn set_recipient(&mut self, recipient: Option<String>) {
+        self.recipient = recipient;
+    }
+
+    pub fn get_keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    pub fn set_keymap(&mut self, keymap: Keymap) {
+        self.keymap = keymap;
+        self.show_cheat_sheet = !self.keymap.get_problems().is_empty();
+    }
+
+    pub fn is_showing_cheat_sheet(&self) -> bool {
+        self.show_cheat_sheet
+    }
+
+    pub fn toggle_cheat_sheet(&mut self) {
+        self.show_cheat_sheet = !self.show_cheat_sheet;
+    }
+
+    pub fn get_inspection(&self) -> Option<&(PathBuf, Result<Inspection, String>)> {
+        self.inspection.as_ref()
+    }
+
+    pub fn set_inspection(&mut self, inspection: Option<(PathBuf, Result<Inspection, String>)>) {
+        self.inspection = inspection;
+        self.recipients_editor = None;
+    }
+
+    // Recipients can only be changed with a private key that is one of them,
+    // since the content key has to be unwrapped first.
+    pub fn get_rewrap_key(&self) -> Option<RsaPrivateKey> {
+        let (_, Ok(inspection)) = self.inspection.as_ref()? else {
+            return None;
+        };
+        let private_key = self.private_key.as_ref()?;
+        let key_fingerprint = fingerprint(&private_key.to_public_key());
+        inspection
+            .recipients
+            .iter()
+            .any(|(recipient, _)| *recipient == key_fingerprint)
+            .then(|| private_key.clone())
+    }
+
+    // Keys that can be added as recipients: the loaded keys and all contacts.
+    pub fn get_recipient_candidates(&self) -> Vec<(String, RsaPublicKey)> {
+        let own_keys = [self.private_key.as_ref().map(|private_key| private_key.to_public_key()), self.public_key.clone()];
+        let mut candidates: Vec<(String, RsaPublicKey)> = Vec::new();
+        for public_key in own_keys.into_iter().flatten().chain(self.contacts.iter().map(|contact| contact.public_key.clone())) {
+            let key_fingerprint = fingerprint(&public_key);
+            if candidates.iter().all(|(candidate, _)| *candidate != key_fingerprint) {
+                candidates.push((key_fingerprint, public_key));
+            }
+        }
+        candidates
+    }
+
+    pub fn get_recipients_editor(&self) -> Option<&[String]> {
+        self.recipients_editor.as_deref()
+    }
+
+    pub fn open_recipients_editor(&mut self) {
+        if let Some((_, Ok(inspection))) = &self.inspection {
+            self.recipients_editor = Some(inspection.recipients.iter().map(|(recipient, _)| recipient.clone()).collect());
+        }
+    }
+
+    pub fn close_recipients_editor(&mut self) {
+        self.recipients_editor = None;
+    }
+
+    pub fn select_recipient_key(&mut self, key_fingerprint: String, selected: bool) {
+        if let Some(selection) = &mut self.recipients_editor {
+            selection.retain(|recipient| *recipient != key_fingerprint);
+            if selected {
+                selection.push(key_fingerprint);
+            }
+        }
+    }
+
+    pub fn is_saving_recipients(&self) -> bool {
+        self.saving_recipients
+    }
+
+    pub fn set_saving_recipients(&mut self, saving_recipients: bool) {
+        self.saving_recipients = saving_recipients;
+    }
+
+    pub fn find_key_owner(&self, key_fingerprint: &str) -> Option<KeyOwner> {
+        if self.private_key.as_ref().is_some_and(|private_key| fingerprint(&private_key.to_public_key()) == key_fingerprint) {
+            return Some(KeyOwner::PrivateKey);
+        }
+        if self.public_key.as_ref().is_some_and(|public_key| fingerprint(public_key) == key_fingerprint) {
+            return Some(KeyOwner::PublicKey);
+        }
+        self.contacts
+            .iter()
+            .find(|contact| contact.fingerprint == key_fingerprint)
+            .map(|contact| KeyOwner::Contact(contact.name.clone()))
+    }
+
+    pub fn is_showing_benchmark(&self) -> bool {
+        self.show_benchmark
+    }
+
+    pub fn toggle_benchmark(&mut self) {
+        self.show_benchmark = !self.show_benchmark;
+    }
+
+    pub fn get_benchmark(&self) -> Option<&Result<Vec<(AeadAlgorithm, f64)>, String>> {
+        self.benchmark.as_ref()
+    }
+
+    pub fn set_benchmark(&mut self, benchmark: Option<Result<Vec<(AeadAlgorithm, f64)>, String>>) {
+        self.benchmark = benchmark;
+    }
+}
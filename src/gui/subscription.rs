@@ -2,6 +2,8 @@ use iced::{Event, Task};
 
 use super::{message::Message, state::State};
 
+pub use window::drop_file;
+
 pub fn on_event(state: &mut State, event: Event) -> Task<Message> {
     match event {
         Event::Keyboard(event) => keyboard::on_event(state, event),
@@ -11,160 +13,49 @@ pub fn on_event(state: &mut State, event: Event) -> Task<Message> {
 }
 
 mod keyboard {
-    use iced::{keyboard::{key::Named, Event, Key, Modifiers}, Task};
-    use crate::gui::{message::{decrypt, encrypt, generate_priv_key_from_bits, pick_file, save_private_key, save_public_key, Message}, state::{State, ViewState}};
+    use iced::{keyboard::Event, Task};
+    use crate::gui::{message::Message, state::State, update::update};
 
     pub fn on_event(state: &mut State, event: Event) -> Task<Message> {
         match event {
-            Event::KeyPressed { key, modifiers, ..} => on_key_pressed(state, key, modifiers),
-            _ => Task::none(),
-        }
-    }
-    fn on_key_pressed(state: &mut State, key: Key, modifiers: Modifiers) -> Task<Message> {
-        match state.get_view_state() {
-            ViewState::Setting => {
-                match key {
-                    Key::Character(character) => {
-                        if !modifiers.control() && !modifiers.command() {
-                            return Task::none();
-                        }
-                        match character.as_str() {
-                            "g" | "G" => {
-                                let bit = state
-                                    .get_priv_key_text_content()
-                                    .text()
-                                    .trim()
-                                    .trim_matches('g')
-                                    .trim_matches('G')
-                                    .parse()
-                                    .unwrap_or(2048);
-                                generate_priv_key_from_bits(bit)
-                            },
-                            "s" | "S" => {
-                                match (state.get_private_key().is_some(), state.get_public_key().is_some()) {
-                                    (true, true) => save_private_key(state).chain(save_public_key(state)),
-                                    (true, false) => save_private_key(state),
-                                    (false, true) => save_public_key(state),
-                                    (false, false) => Task::none(),
-                                }
-                            }
-                            _ => Task::none()
-                        }
-                    }
-                    Key::Named(named) => {
-                        match named {
-                            Named::Tab => {
-                                state.switch_view_state();
-                                Task::none()
-                            }
-                            _ => Task::none(),
-                        }
-                    }
-                    _ => Task::none()
-                }
-            }
-            ViewState::Encrypting => {
-                match key {
-                    Key::Character(character) => {
-                        if !modifiers.control() && !modifiers.command() {
-                            return Task::none();
-                        }
-                        match character.as_str() {
-                            "s" | "S" => {
-                                encrypt(state)
-                            }
-                            "o" | "O" => {
-                                pick_file()
-                            }
-                            _ => Task::none()
-                        }
-                    }
-                    Key::Named(named) => {
-                        match named {
-                            Named::Tab => {
-                                state.set_file_path(None);
-                                state.switch_view_state();
-                                Task::none()
-                            }
-                            Named::Enter => {
-                                encrypt(state)
-                            }
-                            _ => Task::none(),
-                        }
-                    }
-                    _ => Task::none()
-                }
-            }
-            ViewState::Decrypting => {
-                match key {
-                    Key::Character(character) => {
-                        if !modifiers.control() && !modifiers.command() {
-                            return Task::none();
-                        }
-                        match character.as_str() {
-                            "s" | "S" => {
-                                decrypt(state)
-                            }
-                            "o" | "O" => {
-                                pick_file()
-                            }
-                            _ => Task::none()
-                        }
-                    }
-                    Key::Named(named) => {
-                        match named {
-                            Named::Tab => {
-                                state.set_file_path(None);
-                                state.switch_view_state();
-                                Task::none()
-                            }
-                            Named::Enter => {
-                                decrypt(state)
-                            }
-                            _ => Task::none(),
-                        }
-                    }
-                    _ => Task::none()
+            Event::KeyPressed { key, modifiers, ..} => {
+                match state.get_keymap().action(state.get_view_state(), &key, modifiers) {
+                    Some(action) => update(state, action.message()),
+                    None => Task::none(),
                 }
             }
+            _ => Task::none(),
         }
     }
 }
 
 mod window {
+    use std::path::PathBuf;
     use iced::{window::Event, Task};
-    use crate::gui::{message::{get_key, Message}, state::{State, ViewState}};
+    use crate::gui::{message::{get_key, inspect_dropped_file, Message}, state::{State, ViewState}};
 
     pub fn on_event(state: &mut State, event: Event) -> Task<Message> {
+        match event {
+            Event::FileDropped(path) if path.is_file() => inspect_dropped_file(path),
+            Event::FileDropped(path) => drop_file(state, path),
+            _ => Task::none(),
+        }
+    }
+
+    pub fn drop_file(state: &mut State, path: PathBuf) -> Task<Message> {
         match state.get_view_state() {
-            ViewState::Setting => {
-                match event {
-                    Event::FileDropped(path) => get_key(path),
-                    _ => Task::none(),
-                }
-            }
+            ViewState::Setting => get_key(state, path),
             ViewState::Encrypting => {
-                match event {
-                    Event::FileDropped(path) => {
-                        if *&path.read_dir().is_err() {
-                            state.set_file_path(Some(path.clone()));
-                        }
-                        Task::none()
-                    },
-                    _ => Task::none(),
-                }
+                state.add_job(path);
+                Task::none()
             }
             ViewState::Decrypting => {
-                match event {
-                    Event::FileDropped(path) => {
-                        if *&path.read_dir().is_err() {
-                            state.set_file_path(Some(path.clone()));
-                        }
-                        Task::none()
-                    },
-                    _ => Task::none(),
+                if path.read_dir().is_err() {
+                    state.add_job(path);
                 }
+                Task::none()
             }
+            ViewState::Preferences => Task::none(),
         }
     }
-}
\ No newline at end of file
+}
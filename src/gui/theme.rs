@@ -0,0 +1,40 @@
+use std::sync::LazyLock;
+
+use dark_light::Mode;
+use iced::{theme::Palette, widget::text, Color, Theme};
+
+use crate::core::ThemeMode;
+
+static HIGH_CONTRAST: LazyLock<Theme> = LazyLock::new(|| {
+    Theme::custom("High contrast".to_string(), Palette {
+        background: Color::BLACK,
+        text: Color::WHITE,
+        primary: Color::from_rgb(1., 1., 0.),
+        success: Color::from_rgb(0., 1., 0.4),
+        danger: Color::from_rgb(1., 0.35, 0.35),
+    })
+});
+
+pub fn theme(mode: ThemeMode, system: Mode) -> Theme {
+    match (mode, system) {
+        (ThemeMode::System, Mode::Dark) | (ThemeMode::Dark, _) => Theme::Dark,
+        (ThemeMode::System, _) | (ThemeMode::Light, _) => Theme::Light,
+        (ThemeMode::HighContrast, _) => HIGH_CONTRAST.clone(),
+    }
+}
+
+pub fn success(theme: &Theme) -> text::Style {
+    text::Style { color: Some(theme.extended_palette().success.base.color) }
+}
+
+pub fn warning(theme: &Theme) -> text::Style {
+    let color = match theme.extended_palette().is_dark {
+        true => Color::from_rgb(1., 0.65, 0.),
+        false => Color::from_rgb(0.65, 0.4, 0.),
+    };
+    text::Style { color: Some(color) }
+}
+
+pub fn error(theme: &Theme) -> text::Style {
+    text::Style { color: Some(theme.extended_palette().danger.base.color) }
+}
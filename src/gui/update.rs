@@ -1,5 +1,5 @@
 use iced::Task;
-use super::{message::{priv_key_to_pub_key_and_fill, save_decrypt_result, save_encrypt_result, text_to_priv_key, text_to_pub_key, Message}, state::State, subscription::on_event};
+use super::{message::{apply_conflict_choice, check_key_file, copy_secret_to_clipboard, copy_to_clipboard, decrypt_from_clipboard, decrypt_text, dismiss_toast_later, drop_container, encrypt_text, expire_clipboard, finish_contacts_save, finish_recipients, generate_keys, inspect_file, missing_key, paste_public_key, paste_text_input, pick_default_output_dir, pick_files, pick_inspect_file, pick_output_dir, priv_key_to_pub_key_and_fill, resolve_conflict, restrict_key_file, run_benchmark, run_pending_jobs, save_contacts, save_keys, save_recipients, save_settings, text_to_priv_key, text_to_pub_key, use_stored_key, write_pem_file, Message}, state::{JobStatus, Outcome, Report, State, ToastKind, ViewState}, subscription::{drop_file, on_event}};
 
 pub fn update(state: &mut State, message: Message) -> Task<Message> {
     match message {
@@ -7,43 +7,305 @@ pub fn update(state: &mut State, message: Message) -> Task<Message> {
             on_event(state, event)
         }
         Message::OnPrivateKeyAction(action) => {
+            let is_edit = action.is_edit();
             state.perform_priv_key_text_content(action);
-            text_to_priv_key(state)
+            match is_edit {
+                true => {
+                    state.set_exposed_key_file(None);
+                    text_to_priv_key(state)
+                }
+                false => Task::none(),
+            }
         }
-        Message::SetPrivateKey(private_key) => {
-            state.set_private_key(Some(private_key));
+        Message::SetPrivateKey(generation, private_key, format) if generation == state.get_priv_key_generation() => {
+            state.set_private_key(Some(private_key), Some(format));
             priv_key_to_pub_key_and_fill(state)
         }
         Message::FillPrivateKey(private_key) => {
             state.fill_private_key(private_key);
             priv_key_to_pub_key_and_fill(state)
         }
-        Message::CleanPrivateKey => {
-            state.set_private_key(None);
+        Message::LoadPrivateKeyFile(path, private_key) => {
+            state.fill_private_key(private_key);
+            Task::batch([priv_key_to_pub_key_and_fill(state), check_key_file(path)])
+        }
+        // The warning lives in the Setting view, elsewhere it's also raised
+        // as a toast, e.g. for a stored key picked to decrypt a dropped file.
+        Message::SetExposedKeyFile(exposed_key_file) => {
+            let warning = match (&exposed_key_file, state.get_view_state()) {
+                (Some((path, mode)), view_state) if view_state != ViewState::Setting => {
+                    Some(state.tr_args("private-key-exposed", &[("path", path.display().to_string().into()), ("mode", format!("{:o}", mode).into())]))
+                }
+                _ => None,
+            };
+            state.set_exposed_key_file(exposed_key_file);
+            match warning {
+                Some(warning) => dismiss_toast_later(state.push_toast(ToastKind::Warning, warning)),
+                None => Task::none(),
+            }
+        }
+        Message::RestrictKeyFile => restrict_key_file(state),
+        Message::CleanPrivateKey(generation, diagnostic) if generation == state.get_priv_key_generation() => {
+            state.set_private_key(None, None);
+            state.set_private_key_diagnostic(Some(diagnostic));
             Task::none()
         }
+        Message::SetPassphrase(passphrase) => {
+            state.set_passphrase(passphrase);
+            text_to_priv_key(state)
+        }
         Message::OnPublicKeyAction(action) => {
+            let is_edit = action.is_edit();
             state.perform_pub_key_text_content(action);
+            match is_edit {
+                true => text_to_pub_key(state),
+                false => Task::none(),
+            }
+        }
+        Message::SetPublicKey(generation, public_key, format) if generation == state.get_pub_key_generation() => {
+            state.set_public_key(Some(public_key), Some(format));
+            Task::none()
+        }
+        Message::FillPublicKey(generation, public_key) if generation == state.get_priv_key_generation() => {
+            if state.get_public_key().as_ref() != Some(&public_key) {
+                state.fill_public_key(public_key);
+            }
+            Task::none()
+        }
+        Message::CleanPublicKey(generation, diagnostic) if generation == state.get_pub_key_generation() => {
+            state.set_public_key(None, None);
+            state.set_public_key_diagnostic(Some(diagnostic));
+            Task::none()
+        }
+        Message::AddJobs(paths) => {
+            paths.into_iter().for_each(|path| state.add_job(path));
+            Task::none()
+        }
+        Message::RunJobs(output_dir) => {
+            state.set_job_output_dir(output_dir);
+            run_pending_jobs(state)
+        }
+        Message::FinishJob(index, result) => {
+            state.finish_job(index, result);
+            if state.is_running_jobs() || state.has_conflicts() {
+                return Task::none();
+            }
+            let jobs = state.get_jobs();
+            let failed = jobs.iter().filter(|job| matches!(job.status, JobStatus::Failed(_))).count();
+            let warned = jobs.iter().any(|job| matches!(job.status, JobStatus::Done(_, Some(_))));
+            let kind = match failed == 0 && !warned {
+                true => ToastKind::Success,
+                false => ToastKind::Warning,
+            };
+            let text = state.tr_args("jobs-finished", &[("succeeded", (jobs.len() - failed).into()), ("failed", failed.into())]);
+            dismiss_toast_later(state.push_toast(kind, text))
+        }
+        Message::Conflict(conflict) => match state.get_conflict_policy() {
+            Some(choice) => apply_conflict_choice(state, vec![conflict], choice),
+            None => {
+                state.push_conflict(conflict);
+                Task::none()
+            }
+        },
+        Message::ResolveConflict(choice) => resolve_conflict(state, choice),
+        Message::SetApplyToAllConflicts(apply_to_all) => {
+            state.set_apply_to_all_conflicts(apply_to_all);
+            Task::none()
+        }
+        Message::ToggleBenchmark => {
+            state.toggle_benchmark();
+            match state.is_showing_benchmark() && state.get_benchmark().is_none() {
+                true => run_benchmark(state),
+                false => Task::none(),
+            }
+        }
+        Message::RunBenchmark => run_benchmark(state),
+        Message::SetBenchmark(result) => {
+            state.set_benchmark(Some(result));
+            Task::none()
+        }
+        Message::SetShredOriginals(shred_originals) => {
+            match shred_originals {
+                true => state.set_confirming_shred(true),
+                false => state.set_shred_originals(false),
+            }
+            Task::none()
+        }
+        Message::ConfirmShred(confirmed) => {
+            match confirmed {
+                true => state.set_shred_originals(true),
+                false => state.set_confirming_shred(false),
+            }
+            Task::none()
+        }
+        Message::WritePemFile(key_file, path, overwrite) => write_pem_file(state, key_file, path, overwrite),
+        Message::OnTextInputAction(action) => {
+            state.perform_text_input_content(action);
+            Task::none()
+        }
+        Message::OnTextOutputAction(action) => {
+            state.perform_text_output_content(action);
+            Task::none()
+        }
+        Message::RunText => match state.get_view_state() {
+            ViewState::Encrypting => encrypt_text(state),
+            ViewState::Decrypting => decrypt_text(state),
+            ViewState::Setting | ViewState::Preferences => Task::none(),
+        },
+        Message::SelectOutputEncoding(output_encoding) => {
+            state.set_output_encoding(output_encoding);
+            Task::none()
+        }
+        Message::CopyToClipboard(text) => copy_to_clipboard(text),
+        Message::CopyTextOutput => {
+            let text = state.get_text_output_content().text();
+            match state.get_view_state() {
+                ViewState::Decrypting => copy_secret_to_clipboard(text),
+                _ => copy_to_clipboard(text),
+            }
+        }
+        Message::PasteTextInput => paste_text_input(),
+        Message::SetTextInput(Some(text)) => {
+            state.set_text_input(&text);
+            Task::none()
+        }
+        Message::PastePublicKey => paste_public_key(),
+        Message::SetPublicKeyText(Some(text)) => {
+            state.set_pub_key_text(&text);
             text_to_pub_key(state)
         }
-        Message::SetPublicKey(public_key) => {
-            state.set_public_key(Some(public_key));
+        Message::DecryptFromClipboard => decrypt_from_clipboard(),
+        Message::DecryptText(Some(text)) => {
+            state.set_text_input(&text);
+            decrypt_text(state)
+        }
+        Message::ExpireClipboard(secret) => expire_clipboard(secret),
+        Message::FinishText(operation, fingerprint, result) => {
+            let outcome = match result {
+                Ok(text) => {
+                    state.set_text_output(&text);
+                    Outcome::Succeeded(None)
+                }
+                Err(err) => {
+                    let text = state.tr_args("text-failed", &[("error", err.as_str().into())]);
+                    state.set_text_output(&text);
+                    Outcome::Failed(err)
+                }
+            };
+            let id = state.notify(Report { operation, input: None, fingerprint, outcome });
+            dismiss_toast_later(id)
+        }
+        Message::SetContacts(contacts) => {
+            state.set_contacts(contacts);
+            Task::none()
+        }
+        // A dropped public key goes into the editor, as before there was a
+        // contact book, and into the book for picking it by name later.
+        Message::LoadPublicKeyFile(contact) => {
+            state.fill_public_key(contact.public_key.clone());
+            state.add_contact(contact);
+            save_contacts(state)
+        }
+        Message::RemoveContact(fingerprint) => {
+            state.remove_contact(&fingerprint);
+            save_contacts(state)
+        }
+        Message::SetContactName(index, name) => {
+            state.set_contact_name(index, name);
+            save_contacts(state)
+        }
+        Message::SetContactEmail(index, email) => {
+            state.set_contact_email(index, email);
+            save_contacts(state)
+        }
+        Message::ContactsSaved(result) => finish_contacts_save(state, result),
+        Message::SelectRecipient(contact) => {
+            state.set_recipient(Some(contact.fingerprint));
+            Task::none()
+        }
+        Message::ClearRecipient => {
+            state.set_recipient(None);
+            Task::none()
+        }
+        Message::SelectView(view_state) => {
+            if !state.is_running_jobs() && state.can_select_view_state(view_state) {
+                state.clear_jobs();
+                state.select_view_state(view_state);
+            }
+            Task::none()
+        }
+        Message::SwitchView => {
+            if !state.is_running_jobs() {
+                state.clear_jobs();
+                state.switch_view_state();
+            }
+            Task::none()
+        }
+        Message::GenerateKeys => generate_keys(state),
+        Message::SaveKeys => save_keys(state),
+        Message::PickFiles => pick_files(state),
+        Message::PickOutputDir => pick_output_dir(state),
+        Message::SetKeymap(keymap) => {
+            state.set_keymap(keymap);
+            Task::none()
+        }
+        Message::SaveSettings(settings) => {
+            state.set_settings(settings);
+            save_settings(state)
+        }
+        Message::PickDefaultOutputDir => pick_default_output_dir(state),
+        Message::ToggleCheatSheet => {
+            state.toggle_cheat_sheet();
+            Task::none()
+        }
+        Message::Report(report) => {
+            let id = state.notify(report);
+            dismiss_toast_later(id)
+        }
+        Message::DismissToast(id) => {
+            state.dismiss_toast(id);
+            Task::none()
+        }
+        Message::ToggleHistory => {
+            state.toggle_history();
+            Task::none()
+        }
+        Message::ClearHistory => {
+            state.clear_history();
+            Task::none()
+        }
+        Message::ClearJobs => {
+            state.clear_jobs();
+            Task::none()
+        }
+        Message::PickInspectFile => pick_inspect_file(state),
+        Message::InspectFile(path) => inspect_file(state, path),
+        Message::SetInspection(path, result) => {
+            state.set_inspection(Some((path, result)));
+            Task::none()
+        }
+        Message::CloseInspection => {
+            state.set_inspection(None);
             Task::none()
         }
-        Message::FillPublicKey(public_key) => {
-            state.fill_public_key(public_key);
+        Message::EditRecipients => {
+            state.open_recipients_editor();
             Task::none()
         }
-        Message::CleanPublicKey => {
-            state.set_public_key(None);
+        Message::SelectRecipientKey(key_fingerprint, selected) => {
+            state.select_recipient_key(key_fingerprint, selected);
             Task::none()
         }
-        Message::SetFilePath(path) => {
-            state.set_file_path(Some(path));
+        Message::SaveRecipients => save_recipients(state),
+        Message::CloseRecipientsEditor => {
+            state.close_recipients_editor();
             Task::none()
         }
-        Message::SaveEncryptResult(data) => save_encrypt_result(state.get_file_name(), data),
-        Message::SaveDecryptResult(data) => save_decrypt_result(state.get_file_name(), data),
-        _ => Task::none() 
+        Message::FinishRecipients(path, result) => finish_recipients(state, path, result),
+        Message::DropFile(path) => drop_file(state, path),
+        Message::DropContainer(path, inspection) => drop_container(state, path, inspection),
+        Message::UseStoredKey(path, key_path, private_key) => use_stored_key(state, path, key_path, private_key),
+        Message::MissingKey(path, inspection) => missing_key(state, path, inspection),
+        _ => Task::none()
     }
 }
\ No newline at end of file
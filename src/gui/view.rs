@@ -1,10 +1,22 @@
-use iced::Element;
+use iced::{widget::{Column, Stack}, Element};
 
-use super::{components::{calculate, setting}, message::Message, state::{State, ViewState}};
+use super::{components::{calculate, inspector, navigation, notification, preferences, setting}, message::Message, state::{State, ViewState}};
 
-pub fn view(state: &State) -> Element<Message> {
-    match state.get_view_state() {
+pub fn view(state: &State) -> Element<'_, Message> {
+    let content = match state.get_view_state() {
         ViewState::Setting => setting::view(state),
+        ViewState::Preferences => preferences::view(state),
         _ => calculate::view(state),
-    }
+    };
+    let content = Column::new().push(navigation::tab_bar(state)).push(content);
+    Stack::new()
+        .push(content)
+        .push_maybe(inspector::view(state))
+        .push_maybe(state.is_showing_history().then(|| notification::history(state)))
+        .push_maybe(state.is_showing_cheat_sheet().then(|| navigation::cheat_sheet(state)))
+        .push_maybe(preferences::benchmark(state))
+        .push_maybe(notification::shred_confirmation(state))
+        .push_maybe(notification::conflict(state))
+        .push(notification::toasts(state))
+        .into()
 }
\ No newline at end of file
@@ -1,4 +1,6 @@
 mod core;
 mod gui;
+mod cli;
 
-pub use gui::run;
\ No newline at end of file
+pub use gui::run;
+pub use cli::run_cli;
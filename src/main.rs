@@ -1,6 +1,13 @@
+use std::{env, process::ExitCode};
+
 use anyhow::Result;
-use rsa_tool::run;
+use rsa_tool::{run, run_cli};
 
-fn main() -> Result<()> {
-    run()
+// Without arguments the GUI starts, anything else is a command line.
+fn main() -> Result<ExitCode> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.is_empty() {
+        true => run().map(|_| ExitCode::SUCCESS),
+        false => run_cli(args),
+    }
 }